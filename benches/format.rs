@@ -0,0 +1,76 @@
+//! Formatting benchmarks, comparing `Display`, the stack allocated `to_str_buf()`, and the
+//! standard library.
+
+#[macro_use]
+extern crate criterion;
+extern crate ipaddr;
+
+use std::fmt::Write;
+use std::hint::black_box;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use criterion::{BenchmarkId, Criterion};
+use ipaddr::{Ipv4Address, Ipv6Address};
+
+const IPV4: &[&str] = &["0.0.0.0", "192.0.2.33", "255.255.255.255"];
+
+const IPV6: &[&str] = &[
+    "::1",
+    "2001:db8::1",
+    "2001:db8:85a3:8d3:1319:8a2e:370:7348",
+    "::ffff:192.0.2.33",
+];
+
+fn ipv4(c: &mut Criterion) {
+    let mut group = c.benchmark_group("format_ipv4");
+    let mut buf = String::with_capacity(64);
+    for input in IPV4 {
+        let ip = Ipv4Address::from_str(input).unwrap();
+        group.bench_with_input(BenchmarkId::new("display", input), &ip, |b, ip| {
+            b.iter(|| {
+                buf.clear();
+                write!(buf, "{}", black_box(ip)).unwrap();
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("to_str_buf", input), &ip, |b, ip| {
+            b.iter(|| black_box(ip).to_str_buf())
+        });
+        let std = Ipv4Addr::from_str(input).unwrap();
+        group.bench_with_input(BenchmarkId::new("std", input), &std, |b, ip| {
+            b.iter(|| {
+                buf.clear();
+                write!(buf, "{}", black_box(ip)).unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+fn ipv6(c: &mut Criterion) {
+    let mut group = c.benchmark_group("format_ipv6");
+    let mut buf = String::with_capacity(64);
+    for input in IPV6 {
+        let ip = Ipv6Address::from_str(input).unwrap();
+        group.bench_with_input(BenchmarkId::new("display", input), &ip, |b, ip| {
+            b.iter(|| {
+                buf.clear();
+                write!(buf, "{}", black_box(ip)).unwrap();
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("to_str_buf", input), &ip, |b, ip| {
+            b.iter(|| black_box(ip).to_str_buf())
+        });
+        let std = Ipv6Addr::from_str(input).unwrap();
+        group.bench_with_input(BenchmarkId::new("std", input), &std, |b, ip| {
+            b.iter(|| {
+                buf.clear();
+                write!(buf, "{}", black_box(ip)).unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, ipv4, ipv6);
+criterion_main!(benches);
@@ -0,0 +1,132 @@
+//! Parsing benchmarks. `from_str` takes the fast paths for the common syntaxes, while
+//! `parse_with` always runs the complete parser, and `std` is the standard library.
+
+#[macro_use]
+extern crate criterion;
+extern crate ipaddr;
+
+use std::hint::black_box;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+use ipaddr::{Ipv4Address, Ipv6Address, ParseOptions};
+
+const IPV4: &[&str] = &["0.0.0.0", "192.0.2.33", "255.255.255.255"];
+
+const IPV6: &[&str] = &[
+    "::1",
+    "2001:db8::1",
+    "2001:db8:85a3:8d3:1319:8a2e:370:7348",
+    "::ffff:192.0.2.33",
+];
+
+fn ipv4(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_ipv4");
+    let options = ParseOptions::new();
+    for input in IPV4 {
+        group.bench_with_input(BenchmarkId::new("from_str", input), input, |b, s| {
+            b.iter(|| Ipv4Address::from_str(black_box(s)))
+        });
+        group.bench_with_input(BenchmarkId::new("parse_with", input), input, |b, s| {
+            b.iter(|| Ipv4Address::parse_with(black_box(s), &options))
+        });
+        group.bench_with_input(BenchmarkId::new("std", input), input, |b, s| {
+            b.iter(|| Ipv4Addr::from_str(black_box(s)))
+        });
+    }
+    group.finish();
+}
+
+fn ipv6(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_ipv6");
+    let options = ParseOptions::new();
+    for input in IPV6 {
+        group.bench_with_input(BenchmarkId::new("from_str", input), input, |b, s| {
+            b.iter(|| Ipv6Address::from_str(black_box(s)))
+        });
+        group.bench_with_input(BenchmarkId::new("parse_with", input), input, |b, s| {
+            b.iter(|| Ipv6Address::parse_with(black_box(s), &options))
+        });
+        group.bench_with_input(BenchmarkId::new("std", input), input, |b, s| {
+            b.iter(|| Ipv6Addr::from_str(black_box(s)))
+        });
+    }
+    group.finish();
+}
+
+/// Parse a batch of addresses that look like the ones found in logs, and report the throughput in
+/// bytes.
+fn batch(c: &mut Criterion) {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let ipv4: Vec<String> = (0..1000)
+        .map(|_| Ipv4Address::from(next() as u32).to_string())
+        .collect();
+    let ipv6: Vec<String> = (0..1000)
+        .map(|_| {
+            // Most addresses in the wild have a run of zeros
+            let bits = u128::from(next()) << 64 | u128::from(next());
+            Ipv6Address::from(bits & !(0xffff_ffff << 32)).to_string()
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("parse_batch");
+    let options = ParseOptions::new();
+    let len: usize = ipv4.iter().map(String::len).sum();
+    group.throughput(Throughput::Bytes(len as u64));
+    group.bench_function("ipv4/from_str", |b| {
+        b.iter(|| {
+            ipv4.iter()
+                .filter(|s| Ipv4Address::from_str(s).is_ok())
+                .count()
+        })
+    });
+    group.bench_function("ipv4/parse_with", |b| {
+        b.iter(|| {
+            ipv4.iter()
+                .filter(|s| Ipv4Address::parse_with(s, &options).is_ok())
+                .count()
+        })
+    });
+    group.bench_function("ipv4/std", |b| {
+        b.iter(|| {
+            ipv4.iter()
+                .filter(|s| Ipv4Addr::from_str(s).is_ok())
+                .count()
+        })
+    });
+
+    let len: usize = ipv6.iter().map(String::len).sum();
+    group.throughput(Throughput::Bytes(len as u64));
+    group.bench_function("ipv6/from_str", |b| {
+        b.iter(|| {
+            ipv6.iter()
+                .filter(|s| Ipv6Address::from_str(s).is_ok())
+                .count()
+        })
+    });
+    group.bench_function("ipv6/parse_with", |b| {
+        b.iter(|| {
+            ipv6.iter()
+                .filter(|s| Ipv6Address::parse_with(s, &options).is_ok())
+                .count()
+        })
+    });
+    group.bench_function("ipv6/std", |b| {
+        b.iter(|| {
+            ipv6.iter()
+                .filter(|s| Ipv6Addr::from_str(s).is_ok())
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, ipv4, ipv6, batch);
+criterion_main!(benches);
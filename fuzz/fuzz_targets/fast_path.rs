@@ -0,0 +1,46 @@
+//! Differential fuzzing of the SWAR fast paths. `from_str()` and `from_ascii()` try them first,
+//! while `parse_with()` always runs the complete parser: the results, including the position and
+//! the reason of errors, must be identical.
+
+#![no_main]
+
+use std::str::{self, FromStr};
+
+use ipaddr::{IpAddress, Ipv4Address, Ipv6Address, ParseOptions};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let s = match str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => {
+            assert!(Ipv4Address::from_ascii(data).is_err());
+            assert!(Ipv6Address::from_ascii(data).is_err());
+            return;
+        }
+    };
+    let options = ParseOptions::new();
+
+    let complete = Ipv4Address::parse_with(s, &options).map_err(|e| e.error());
+    assert_eq!(Ipv4Address::from_str(s).map_err(|e| e.error()), complete);
+    assert_eq!(
+        Ipv4Address::from_ascii(data).map_err(|e| e.error()),
+        complete
+    );
+
+    let complete = Ipv6Address::parse_with(s, &options).map_err(|e| e.error());
+    assert_eq!(Ipv6Address::from_str(s).map_err(|e| e.error()), complete);
+    assert_eq!(
+        Ipv6Address::from_ascii(data).map_err(|e| e.error()),
+        complete
+    );
+
+    let complete = if s.contains(':') {
+        Ipv6Address::parse_with(s, &options).map(IpAddress::V6)
+    } else {
+        Ipv4Address::parse_with(s, &options).map(IpAddress::V4)
+    };
+    assert_eq!(
+        IpAddress::from_str(s).map_err(|e| e.error()),
+        complete.map_err(|e| e.error())
+    );
+});
@@ -0,0 +1,63 @@
+//! Differential fuzzing of the IPv4 address parsers against `std::net::Ipv4Addr`, and
+//! parse → format → parse round trips.
+
+#![no_main]
+
+use std::net::Ipv4Addr;
+use std::str::{self, FromStr};
+
+use ipaddr::{Ipv4Address, Ipv4Notation, ParseOptions};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // The byte and string parsers must agree
+    let from_ascii = Ipv4Address::from_ascii(data).ok();
+    let s = match str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => {
+            assert!(from_ascii.is_none());
+            return;
+        }
+    };
+    let lenient = Ipv4Address::from_str(s).ok();
+    assert_eq!(from_ascii, lenient);
+
+    // Without leading zeros, the parser accepts exactly what std accepts
+    let strict = Ipv4Address::parse_with(s, &ParseOptions::strict()).ok();
+    let std = Ipv4Addr::from_str(s).ok();
+    assert_eq!(strict.map(u32::from), std.map(u32::from), "{:?}", s);
+
+    // Everything std accepts is accepted, and leading zeros are the only difference
+    if let Some(std) = std {
+        assert_eq!(lenient.map(u32::from), Some(u32::from(std)));
+    }
+    if lenient.is_some() && strict.is_none() {
+        assert!(s
+            .split('.')
+            .any(|octet| octet.len() > 1 && octet.starts_with('0')));
+    }
+
+    // inet_aton() forms are a superset of the dotted-decimal form
+    if let Ok((ip, form)) = Ipv4Address::parse_inet_aton(s) {
+        if !form.is_legacy() {
+            assert_eq!(Some(ip), lenient);
+        }
+    }
+
+    if let Some(ip) = lenient {
+        // Round trip, and agreement with the std formatting
+        let formatted = ip.to_string();
+        assert_eq!(formatted, Ipv4Addr::from(u32::from(ip)).to_string());
+        assert_eq!(Ipv4Address::from_str(&formatted).unwrap(), ip);
+        assert_eq!(&*ip.to_str_buf(), formatted);
+
+        let mut s = String::new();
+        ip.formatter(&mut s).leading_zeros(true).write().unwrap();
+        assert_eq!(Ipv4Address::from_str(&s).unwrap(), ip);
+        for notation in &[Ipv4Notation::Hexadecimal, Ipv4Notation::Octal] {
+            s.clear();
+            ip.formatter(&mut s).notation(*notation).write().unwrap();
+            assert_eq!(Ipv4Address::parse_inet_aton(&s).unwrap().0, ip);
+        }
+    }
+});
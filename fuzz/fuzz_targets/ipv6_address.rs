@@ -0,0 +1,72 @@
+//! Differential fuzzing of the IPv6 address parsers against `std::net::Ipv6Addr`, and
+//! parse → format → parse round trips with every formatter option.
+
+#![no_main]
+
+use std::net::Ipv6Addr;
+use std::str::{self, FromStr};
+
+use ipaddr::{Ipv4Suffix, Ipv6Address, ParseOptions, ScopedIpv6Address};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // The byte and string parsers must agree
+    let from_ascii = Ipv6Address::from_ascii(data).ok();
+    let s = match str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => {
+            assert!(from_ascii.is_none());
+            return;
+        }
+    };
+    let lenient = Ipv6Address::from_str(s).ok();
+    assert_eq!(from_ascii, lenient);
+
+    // Without leading zeros in the embedded IPv4 address, the parser accepts exactly what std
+    // accepts
+    let strict = Ipv6Address::parse_with(s, &ParseOptions::strict()).ok();
+    let std = Ipv6Addr::from_str(s).ok();
+    assert_eq!(strict.map(u128::from), std.map(u128::from), "{:?}", s);
+    if let Some(std) = std {
+        assert_eq!(lenient.map(u128::from), Some(u128::from(std)));
+    }
+
+    // The other options only widen what is accepted
+    let all = Ipv6Address::parse_with(s, &ParseOptions::lenient()).ok();
+    if lenient.is_some() {
+        assert_eq!(all, lenient);
+    }
+    let _ = ScopedIpv6Address::from_str(s);
+
+    if let Some(ip) = lenient {
+        // Round trip, and agreement with the std formatting. std writes IPv4-mapped addresses
+        // with an IPv4 suffix.
+        let formatted = ip.to_string();
+        let std_ip = Ipv6Addr::from(u128::from(ip));
+        if std_ip.to_ipv4_mapped().is_none() {
+            assert_eq!(formatted, std_ip.to_string());
+        }
+        assert_eq!(Ipv6Address::from_str(&formatted).unwrap(), ip);
+        assert_eq!(&*ip.to_str_buf(), formatted);
+
+        let mut s = String::new();
+        for &ellipsis in &[false, true] {
+            for &leading_zeros in &[false, true] {
+                for &upper_case in &[false, true] {
+                    for &suffix in &[Ipv4Suffix::Never, Ipv4Suffix::Always, Ipv4Suffix::Auto] {
+                        s.clear();
+                        ip.formatter(&mut s)
+                            .ellipsis(ellipsis)
+                            .leading_zeros(leading_zeros)
+                            .upper_case(upper_case)
+                            .ipv4_suffix(suffix)
+                            .write()
+                            .unwrap();
+                        assert_eq!(Ipv6Address::from_str(&s).unwrap(), ip, "{:?}", s);
+                        assert_eq!(Ipv6Addr::from_str(&s).unwrap(), std_ip, "{:?}", s);
+                    }
+                }
+            }
+        }
+    }
+});
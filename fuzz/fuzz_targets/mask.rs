@@ -0,0 +1,51 @@
+//! Fuzzing of the IPv4 and IPv6 mask parsers: a mask is an address with contiguous ones, so the
+//! parsers are checked against `std::net` and the prefix length.
+
+#![no_main]
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::{self, FromStr};
+
+use ipaddr::{Ipv4Address, Ipv4Mask, Ipv6Address, Ipv6Mask};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let mask4 = Ipv4Mask::from_ascii(data).ok();
+    let mask6 = Ipv6Mask::from_ascii(data).ok();
+    let s = match str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => {
+            assert!(mask4.is_none() && mask6.is_none());
+            return;
+        }
+    };
+    assert_eq!(Ipv4Mask::from_str(s).ok(), mask4);
+    assert_eq!(Ipv6Mask::from_str(s).ok(), mask6);
+
+    // A mask is an address that is a valid mask
+    let ip4 = Ipv4Address::from_str(s).ok().map(u32::from);
+    let valid4 = ip4.filter(|value| value.leading_ones() + value.trailing_zeros() == 32);
+    assert_eq!(mask4.map(u32::from), valid4);
+    let ip6 = Ipv6Address::from_str(s).ok().map(u128::from);
+    let valid6 = ip6.filter(|value| value.leading_ones() + value.trailing_zeros() == 128);
+    assert_eq!(mask6.map(u128::from), valid6);
+
+    if let Some(mask) = mask4 {
+        let prefixlen = mask.prefixlen();
+        assert_eq!(Ipv4Mask::from_prefixlen(prefixlen as u8).unwrap(), mask);
+        assert_eq!(Ipv4Mask::from_str(&mask.to_string()).unwrap(), mask);
+        assert_eq!(
+            mask.to_string(),
+            Ipv4Addr::from(u32::from(mask)).to_string()
+        );
+    }
+    if let Some(mask) = mask6 {
+        let prefixlen = mask.prefixlen();
+        assert_eq!(Ipv6Mask::from_prefixlen(prefixlen as u8).unwrap(), mask);
+        assert_eq!(Ipv6Mask::from_str(&mask.to_string()).unwrap(), mask);
+        assert_eq!(
+            mask.to_string(),
+            Ipv6Addr::from(u128::from(mask)).to_string()
+        );
+    }
+});
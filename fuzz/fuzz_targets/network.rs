@@ -0,0 +1,73 @@
+//! Fuzzing of the IPv4 and IPv6 network parsers against a reference built on `std::net`, and
+//! parse → format → parse round trips.
+
+#![no_main]
+
+use std::str::{self, FromStr};
+
+use ipaddr::{Ipv4Address, Ipv4Mask, Ipv4Network, Ipv6Address, Ipv6Mask, Ipv6Network};
+use libfuzzer_sys::fuzz_target;
+
+/// Split a network into its address and its mask, which is either a prefix length that is at
+/// most `max`, or a mask.
+fn split(s: &str, max: u8) -> Option<(&str, Result<u8, &str>)> {
+    let (ip, mask) = s.split_once('/')?;
+    if !mask.is_empty() && mask.bytes().all(|b| b.is_ascii_digit()) {
+        let prefixlen = mask.trim_start_matches('0');
+        if prefixlen.len() > 3 {
+            return None;
+        }
+        let prefixlen = prefixlen.parse::<u16>().unwrap_or(0);
+        return if prefixlen <= u16::from(max) {
+            Some((ip, Ok(prefixlen as u8)))
+        } else {
+            None
+        };
+    }
+    Some((ip, Err(mask)))
+}
+
+fn reference4(s: &str) -> Option<Ipv4Network> {
+    let (ip, mask) = split(s, 32)?;
+    let ip = Ipv4Address::from_str(ip).ok()?;
+    let mask = match mask {
+        Ok(prefixlen) => Ipv4Mask::from_prefixlen(prefixlen).unwrap(),
+        Err(mask) => Ipv4Mask::from_str(mask).ok()?,
+    };
+    Some(Ipv4Network::new(ip, mask))
+}
+
+fn reference6(s: &str) -> Option<Ipv6Network> {
+    let (ip, mask) = split(s, 128)?;
+    let ip = Ipv6Address::from_str(ip).ok()?;
+    let mask = match mask {
+        Ok(prefixlen) => Ipv6Mask::from_prefixlen(prefixlen).unwrap(),
+        Err(mask) => Ipv6Mask::from_str(mask).ok()?,
+    };
+    Some(Ipv6Network::new(ip, mask))
+}
+
+fuzz_target!(|data: &[u8]| {
+    let net4 = Ipv4Network::from_ascii(data).ok();
+    let net6 = Ipv6Network::from_ascii(data).ok();
+    let s = match str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => {
+            assert!(net4.is_none() && net6.is_none());
+            return;
+        }
+    };
+    assert_eq!(Ipv4Network::from_str(s).ok(), net4);
+    assert_eq!(Ipv6Network::from_str(s).ok(), net6);
+
+    // The address and mask parsers are checked against std by the other targets
+    assert_eq!(net4, reference4(s), "{:?}", s);
+    assert_eq!(net6, reference6(s), "{:?}", s);
+
+    if let Some(net) = net4 {
+        assert_eq!(Ipv4Network::from_str(&net.to_string()).unwrap(), net);
+    }
+    if let Some(net) = net6 {
+        assert_eq!(Ipv6Network::from_str(&net.to_string()).unwrap(), net);
+    }
+});
@@ -0,0 +1,25 @@
+//! Fuzzing of the reverse DNS name parsers, with name → address → name round trips.
+
+#![no_main]
+
+use std::str;
+
+use ipaddr::{Ipv4Address, Ipv6Address};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let s = match str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+    if let Ok(ip) = Ipv4Address::from_reverse_dns(s) {
+        let name = ip.to_reverse_dns();
+        assert_eq!(Ipv4Address::from_reverse_dns(&name).unwrap(), ip);
+    }
+    if let Ok(ip) = Ipv6Address::from_reverse_dns(s) {
+        let name = ip.to_reverse_dns();
+        assert_eq!(Ipv6Address::from_reverse_dns(&name).unwrap(), ip);
+        // Each nibble has exactly one representation, up to the case
+        assert!(name.eq_ignore_ascii_case(s.strip_suffix('.').unwrap_or(s)));
+    }
+});
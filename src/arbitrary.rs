@@ -0,0 +1,118 @@
+//! Edge-case biased generation of addresses, masks and networks, shared by the `proptest` and
+//! `quickcheck` implementations. Each function turns raw random values into a value of the given
+//! kind, so that both frameworks produce the same distribution.
+
+use {
+    Ipv4Address, Ipv4Mask, Ipv4Network, Ipv6Address, Ipv6Mask, Ipv6Network, IPV4_MAX_PREFIXLEN,
+    IPV6_MAX_PREFIXLEN,
+};
+
+/// Number of kinds of IPv4 addresses generated by `ipv4_address`.
+pub(crate) const IPV4_KINDS: u8 = 6;
+
+/// Number of kinds of IPv6 addresses generated by `ipv6_address`.
+pub(crate) const IPV6_KINDS: u8 = 9;
+
+/// Number of kinds of prefix lengths generated by `ipv4_prefixlen` and `ipv6_prefixlen`.
+pub(crate) const PREFIXLEN_KINDS: u8 = 8;
+
+/// Return an IPv4 address. Half of the kinds are uniformly distributed, the others are:
+///
+/// - `0.0.0.0`, `255.255.255.255` and `127.0.0.1`
+/// - a multicast address
+/// - the first or the last address of a random prefix
+pub(crate) fn ipv4_address(kind: u8, bits: u32, prefixlen: u8) -> Ipv4Address {
+    let value = match kind % IPV4_KINDS {
+        0..=2 => bits,
+        3 => [0, u32::MAX, 0x7f00_0001][bits as usize % 3],
+        4 => 0xe000_0000 | (bits >> 4),
+        _ => {
+            let mask = u32::from(Ipv4Mask::from_prefixlen(prefixlen % 33).unwrap());
+            if bits & 1 == 0 {
+                bits & mask
+            } else {
+                bits | !mask
+            }
+        }
+    };
+    Ipv4Address(value)
+}
+
+/// Return an IPv6 address. A third of the kinds are uniformly distributed, the others are:
+///
+/// - `::`, `::1` and `ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff`
+/// - an IPv4-mapped address
+/// - an IPv4-compatible address, or an address in the NAT64 well-known prefix
+/// - a multicast address
+/// - an address with random runs of zero hextets, including runs of equal length
+/// - the first or the last address of a random prefix
+pub(crate) fn ipv6_address(kind: u8, bits: u128, prefixlen: u8) -> Ipv6Address {
+    let value = match kind % IPV6_KINDS {
+        0..=2 => bits,
+        3 => [0, 1, u128::MAX][bits as usize % 3],
+        4 => 0xffff_0000_0000 | (bits & 0xffff_ffff),
+        5 => {
+            if bits & (1 << 32) == 0 {
+                bits & 0xffff_ffff
+            } else {
+                0x0064_ff9b << 96 | (bits & 0xffff_ffff)
+            }
+        }
+        6 => 0xff << 120 | (bits >> 8),
+        7 => {
+            // The 8 most significant bits select which hextets are zeroed.
+            let zeros = (bits >> 120) as u8;
+            (0..8).fold(bits, |value, i| {
+                if zeros & (1 << i) != 0 {
+                    value & !(0xffff << (16 * i))
+                } else {
+                    value
+                }
+            })
+        }
+        _ => {
+            let mask = u128::from(Ipv6Mask::from_prefixlen(prefixlen % 129).unwrap());
+            if bits & 1 == 0 {
+                bits & mask
+            } else {
+                bits | !mask
+            }
+        }
+    };
+    Ipv6Address(value)
+}
+
+/// Return an IPv4 prefix length, biased toward `/0`, `/31` and `/32`.
+pub(crate) fn ipv4_prefixlen(kind: u8, len: u8) -> u8 {
+    match kind % PREFIXLEN_KINDS {
+        0 => 0,
+        1 => 31,
+        2 => IPV4_MAX_PREFIXLEN,
+        _ => len % (IPV4_MAX_PREFIXLEN + 1),
+    }
+}
+
+/// Return an IPv6 prefix length, biased toward `/0`, `/64`, `/127` and `/128`.
+pub(crate) fn ipv6_prefixlen(kind: u8, len: u8) -> u8 {
+    match kind % PREFIXLEN_KINDS {
+        0 => 0,
+        1 => 64,
+        2 => 127,
+        3 => IPV6_MAX_PREFIXLEN,
+        _ => len % (IPV6_MAX_PREFIXLEN + 1),
+    }
+}
+
+/// Return an IPv4 network. If `aligned` is `true`, the host bits of the address are unset.
+pub(crate) fn ipv4_network(ip: Ipv4Address, prefixlen: u8, aligned: bool) -> Ipv4Network {
+    let mask = Ipv4Mask::from_prefixlen(prefixlen).unwrap();
+    let ip = if aligned { ip & mask } else { ip };
+    Ipv4Network::new(ip, mask)
+}
+
+/// Return an IPv6 network. If `aligned` is `true`, the host bits of the address are unset.
+pub(crate) fn ipv6_network(ip: Ipv6Address, prefixlen: u8, aligned: bool) -> Ipv6Network {
+    let mask = Ipv6Mask::from_prefixlen(prefixlen).unwrap();
+    let ip = if aligned { ip & mask } else { ip };
+    Ipv6Network::new(ip, mask)
+}
@@ -0,0 +1,146 @@
+//! Conversions to and from [Apache Arrow](https://docs.rs/arrow-array) arrays. IPv4 addresses
+//! are stored as `UInt32` values, and IPv6 addresses as `FixedSizeBinary(16)` values, in network
+//! byte order. Building an array moves the buffer that holds the addresses into the array, without
+//! copying it.
+
+use arrow_array::{Array, FixedSizeBinaryArray, UInt32Array};
+use arrow_buffer::Buffer;
+
+use {Ipv4Address, Ipv6Address, MalformedAddress};
+
+impl Ipv4Address {
+    /// Build a `UInt32` array from the given addresses.
+    ///
+    /// ```rust
+    /// # extern crate arrow_array;
+    /// # extern crate ipaddr;
+    /// # use arrow_array::Array;
+    /// # use ipaddr::Ipv4Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ips = [Ipv4Address::from_str("192.0.2.33").unwrap()];
+    /// let array = Ipv4Address::to_arrow(&ips);
+    /// assert_eq!(array.len(), 1);
+    /// assert_eq!(array.value(0), 0xc000_0221);
+    /// # }
+    /// ```
+    pub fn to_arrow(addresses: &[Ipv4Address]) -> UInt32Array {
+        UInt32Array::from(addresses.iter().map(|ip| ip.0).collect::<Vec<u32>>())
+    }
+
+    /// Read the addresses of a `UInt32` array. Null values are `None`.
+    ///
+    /// ```rust
+    /// # extern crate arrow_array;
+    /// # extern crate ipaddr;
+    /// # use arrow_array::UInt32Array;
+    /// # use ipaddr::Ipv4Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let array = UInt32Array::from(vec![Some(0xc000_0221), None]);
+    /// assert_eq!(
+    ///     Ipv4Address::from_arrow(&array),
+    ///     vec![Some(Ipv4Address::from_str("192.0.2.33").unwrap()), None]
+    /// );
+    /// # }
+    /// ```
+    pub fn from_arrow(array: &UInt32Array) -> Vec<Option<Ipv4Address>> {
+        array.iter().map(|value| value.map(Ipv4Address)).collect()
+    }
+}
+
+impl Ipv6Address {
+    /// Build a `FixedSizeBinary(16)` array from the given addresses.
+    ///
+    /// ```rust
+    /// # extern crate arrow_array;
+    /// # extern crate ipaddr;
+    /// # use arrow_array::Array;
+    /// # use ipaddr::Ipv6Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = Ipv6Address::from_str("2001:db8::1").unwrap();
+    /// let array = Ipv6Address::to_arrow(&[ip]);
+    /// assert_eq!(array.len(), 1);
+    /// assert_eq!(array.value(0), &ip.octets()[..]);
+    /// # }
+    /// ```
+    pub fn to_arrow(addresses: &[Ipv6Address]) -> FixedSizeBinaryArray {
+        let mut values = Vec::with_capacity(addresses.len() * 16);
+        for ip in addresses {
+            values.extend_from_slice(&ip.octets());
+        }
+        FixedSizeBinaryArray::new(16, Buffer::from_vec(values), None)
+    }
+
+    /// Read the addresses of a `FixedSizeBinary(16)` array. Null values are `None`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the values of the array are not 16 bytes long.
+    ///
+    /// ```rust
+    /// # extern crate arrow_array;
+    /// # extern crate ipaddr;
+    /// # use arrow_array::FixedSizeBinaryArray;
+    /// # use ipaddr::Ipv6Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = Ipv6Address::from_str("2001:db8::1").unwrap();
+    /// let values = vec![Some(ip.octets()), None];
+    /// let array = FixedSizeBinaryArray::try_from_sparse_iter_with_size(values.into_iter(), 16)
+    ///     .unwrap();
+    /// assert_eq!(Ipv6Address::from_arrow(&array).unwrap(), vec![Some(ip), None]);
+    ///
+    /// let array = FixedSizeBinaryArray::try_from_iter(vec![[0u8; 4]].into_iter()).unwrap();
+    /// assert!(Ipv6Address::from_arrow(&array).is_err());
+    /// # }
+    /// ```
+    pub fn from_arrow(
+        array: &FixedSizeBinaryArray,
+    ) -> Result<Vec<Option<Ipv6Address>>, MalformedAddress> {
+        if array.value_length() != 16 {
+            return Err(MalformedAddress);
+        }
+        Ok((0..array.len())
+            .map(|i| {
+                if array.is_null(i) {
+                    return None;
+                }
+                let mut octets = [0; 16];
+                octets.copy_from_slice(array.value(i));
+                Some(Ipv6Address::from(octets))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let ipv4: Vec<Ipv4Address> = (0..100u32)
+            .map(|i| Ipv4Address(i.wrapping_mul(0x9e37_79b9)))
+            .collect();
+        let array = Ipv4Address::to_arrow(&ipv4);
+        assert_eq!(array.null_count(), 0);
+        let parsed: Vec<_> = Ipv4Address::from_arrow(&array)
+            .into_iter()
+            .map(Option::unwrap)
+            .collect();
+        assert_eq!(parsed, ipv4);
+
+        let ipv6: Vec<Ipv6Address> = ipv4.iter().map(|ip| ip.to_ipv6_mapped()).collect();
+        let array = Ipv6Address::to_arrow(&ipv6);
+        assert_eq!(array.len(), 100);
+        assert_eq!(array.value(1), &ipv6[1].octets()[..]);
+        let parsed: Vec<_> = Ipv6Address::from_arrow(&array)
+            .unwrap()
+            .into_iter()
+            .map(Option::unwrap)
+            .collect();
+        assert_eq!(parsed, ipv6);
+    }
+}
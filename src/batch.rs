@@ -0,0 +1,373 @@
+//! Parsing and formatting of many addresses at once, for columnar data and line oriented files.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+#[cfg(feature = "alloc")]
+use core::str::FromStr;
+
+use {IpAddress, Ipv4Address, Ipv6Address, ParsingFailed};
+
+/// Maximum length of an IPv4 address formatted by `Display`, like `255.255.255.255`.
+#[cfg(feature = "alloc")]
+const IPV4_MAX_LEN: usize = 15;
+
+/// Maximum length of an IPv6 address formatted by `Display`, like
+/// `ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff`.
+#[cfg(feature = "alloc")]
+const IPV6_MAX_LEN: usize = 39;
+
+/// An address that can be parsed from ASCII bytes. This is implemented by
+/// [`Ipv4Address`](struct.Ipv4Address.html), [`Ipv6Address`](struct.Ipv6Address.html) and
+/// [`IpAddress`](enum.IpAddress.html).
+pub trait FromAscii: Sized {
+    /// Parse an address from ASCII bytes.
+    fn from_ascii(bytes: &[u8]) -> Result<Self, ParsingFailed>;
+}
+
+impl FromAscii for Ipv4Address {
+    fn from_ascii(bytes: &[u8]) -> Result<Self, ParsingFailed> {
+        Ipv4Address::from_ascii(bytes)
+    }
+}
+
+impl FromAscii for Ipv6Address {
+    fn from_ascii(bytes: &[u8]) -> Result<Self, ParsingFailed> {
+        Ipv6Address::from_ascii(bytes)
+    }
+}
+
+impl FromAscii for IpAddress {
+    fn from_ascii(bytes: &[u8]) -> Result<Self, ParsingFailed> {
+        IpAddress::from_ascii(bytes)
+    }
+}
+
+/// An iterator over the lines of a text, that parses each line as an address. It yields the line
+/// number, starting at 1, along with the result of parsing the line.
+///
+/// Lines are separated by `\n`, and a trailing `\r` is removed. Empty lines are parsing errors,
+/// unless they are skipped with [`skip_empty()`](#method.skip_empty). A newline at the end of the
+/// text does not start a new line.
+///
+/// ```rust
+/// # use ipaddr::{Ipv4Address, ParseLines};
+/// # use std::str::FromStr;
+/// # fn main() {
+/// let text = b"192.0.2.1\r\n\n192.0.2.256\n192.0.2.3\n";
+/// let lines: Vec<_> = ParseLines::<Ipv4Address>::new(text).skip_empty(true).collect();
+/// assert_eq!(lines.len(), 3);
+///
+/// assert_eq!(lines[0].0, 1);
+/// assert_eq!(*lines[0].1.as_ref().unwrap(), Ipv4Address::from_str("192.0.2.1").unwrap());
+/// assert_eq!(lines[1].0, 3);
+/// assert!(lines[1].1.is_err());
+/// assert_eq!(lines[2].0, 4);
+/// # }
+/// ```
+pub struct ParseLines<'a, T> {
+    text: &'a [u8],
+    line: usize,
+    skip_empty: bool,
+    address: PhantomData<T>,
+}
+
+impl<'a, T: FromAscii> ParseLines<'a, T> {
+    /// Create an iterator over the lines of the given text.
+    pub fn new(text: &'a [u8]) -> Self {
+        ParseLines {
+            text,
+            line: 0,
+            skip_empty: false,
+            address: PhantomData,
+        }
+    }
+
+    /// Skip the empty lines instead of returning an error. This is `false` by default.
+    pub fn skip_empty(mut self, flag: bool) -> Self {
+        self.skip_empty = flag;
+        self
+    }
+}
+
+impl<'a, T: FromAscii> Iterator for ParseLines<'a, T> {
+    type Item = (usize, Result<T, ParsingFailed>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.text.is_empty() {
+            let (line, rest) = match self.text.iter().position(|b| *b == b'\n') {
+                Some(end) => (&self.text[..end], &self.text[end + 1..]),
+                None => (self.text, &[][..]),
+            };
+            self.text = rest;
+            self.line += 1;
+
+            let line = match line.split_last() {
+                Some((b'\r', line)) => line,
+                _ => line,
+            };
+            if line.is_empty() && self.skip_empty {
+                continue;
+            }
+            return Some((self.line, T::from_ascii(line)));
+        }
+        None
+    }
+}
+
+impl Ipv4Address {
+    /// Parse each of the given strings. The results are in the same order as the inputs.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let results = Ipv4Address::parse_many(&["192.0.2.1", "192.0.2", "192.0.2.3"]);
+    /// assert_eq!(*results[0].as_ref().unwrap(), Ipv4Address::from_str("192.0.2.1").unwrap());
+    /// assert!(results[1].is_err());
+    /// assert_eq!(*results[2].as_ref().unwrap(), Ipv4Address::from_str("192.0.2.3").unwrap());
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn parse_many<S: AsRef<str>>(inputs: &[S]) -> Vec<Result<Self, ParsingFailed>> {
+        inputs
+            .iter()
+            .map(|s| Ipv4Address::from_str(s.as_ref()))
+            .collect()
+    }
+
+    /// Parse each line of the given text. See [`ParseLines`](struct.ParseLines.html).
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Address;
+    /// # fn main() {
+    /// let errors: Vec<usize> = Ipv4Address::parse_lines(b"192.0.2.1\n192.0.2\n192.0.2.3")
+    ///     .filter(|(_, result)| result.is_err())
+    ///     .map(|(line, _)| line)
+    ///     .collect();
+    /// assert_eq!(errors, vec![2]);
+    /// # }
+    /// ```
+    pub fn parse_lines<'a>(text: &'a [u8]) -> ParseLines<'a, Self> {
+        ParseLines::new(text)
+    }
+
+    /// Append the given addresses to `out`, separated by `separator`. Enough space is reserved up
+    /// front, so that `out` is not reallocated while formatting, and can be reused for the next
+    /// batch.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ips = [
+    ///     Ipv4Address::from_str("192.0.2.1").unwrap(),
+    ///     Ipv4Address::from_str("192.0.2.2").unwrap(),
+    /// ];
+    /// let mut out = String::new();
+    /// Ipv4Address::format_into(&ips, &mut out, ",");
+    /// assert_eq!(out, "192.0.2.1,192.0.2.2");
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn format_into(addresses: &[Self], out: &mut String, separator: &str) {
+        out.reserve(addresses.len() * (IPV4_MAX_LEN + separator.len()));
+        for (i, ip) in addresses.iter().enumerate() {
+            if i > 0 {
+                out.push_str(separator);
+            }
+            out.push_str(&ip.to_str_buf());
+        }
+    }
+}
+
+impl Ipv6Address {
+    /// Parse each of the given strings. The results are in the same order as the inputs.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let inputs = vec![String::from("2001:db8::1"), String::from("2001:db8::1::2")];
+    /// let results = Ipv6Address::parse_many(&inputs);
+    /// assert_eq!(*results[0].as_ref().unwrap(), Ipv6Address::from_str("2001:db8::1").unwrap());
+    /// assert!(results[1].is_err());
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn parse_many<S: AsRef<str>>(inputs: &[S]) -> Vec<Result<Self, ParsingFailed>> {
+        inputs
+            .iter()
+            .map(|s| Ipv6Address::from_str(s.as_ref()))
+            .collect()
+    }
+
+    /// Parse each line of the given text. See [`ParseLines`](struct.ParseLines.html).
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Address;
+    /// # fn main() {
+    /// let valid = Ipv6Address::parse_lines(b"2001:db8::1\r\nfe80::1%eth0\r\n")
+    ///     .filter(|(_, result)| result.is_ok())
+    ///     .count();
+    /// assert_eq!(valid, 1);
+    /// # }
+    /// ```
+    pub fn parse_lines<'a>(text: &'a [u8]) -> ParseLines<'a, Self> {
+        ParseLines::new(text)
+    }
+
+    /// Append the given addresses to `out`, separated by `separator`. Enough space is reserved up
+    /// front, so that `out` is not reallocated while formatting, and can be reused for the next
+    /// batch.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ips = [
+    ///     Ipv6Address::from_str("2001:db8::1").unwrap(),
+    ///     Ipv6Address::from_str("fe80::1").unwrap(),
+    /// ];
+    /// let mut out = String::new();
+    /// Ipv6Address::format_into(&ips, &mut out, "\n");
+    /// assert_eq!(out, "2001:db8::1\nfe80::1");
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn format_into(addresses: &[Self], out: &mut String, separator: &str) {
+        out.reserve(addresses.len() * (IPV6_MAX_LEN + separator.len()));
+        for (i, ip) in addresses.iter().enumerate() {
+            if i > 0 {
+                out.push_str(separator);
+            }
+            out.push_str(&ip.to_str_buf());
+        }
+    }
+}
+
+impl IpAddress {
+    /// Parse each of the given strings. The results are in the same order as the inputs.
+    ///
+    /// ```rust
+    /// # use ipaddr::IpAddress;
+    /// # fn main() {
+    /// let results = IpAddress::parse_many(&["192.0.2.1", "2001:db8::1"]);
+    /// assert!(results[0].as_ref().unwrap().is_ipv4());
+    /// assert!(results[1].as_ref().unwrap().is_ipv6());
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn parse_many<S: AsRef<str>>(inputs: &[S]) -> Vec<Result<Self, ParsingFailed>> {
+        inputs
+            .iter()
+            .map(|s| IpAddress::from_str(s.as_ref()))
+            .collect()
+    }
+
+    /// Parse each line of the given text. See [`ParseLines`](struct.ParseLines.html).
+    ///
+    /// ```rust
+    /// # use ipaddr::IpAddress;
+    /// # fn main() {
+    /// let ipv6 = IpAddress::parse_lines(b"192.0.2.1\n2001:db8::1\n")
+    ///     .filter(|(_, result)| result.as_ref().map(IpAddress::is_ipv6).unwrap_or(false))
+    ///     .count();
+    /// assert_eq!(ipv6, 1);
+    /// # }
+    /// ```
+    pub fn parse_lines<'a>(text: &'a [u8]) -> ParseLines<'a, Self> {
+        ParseLines::new(text)
+    }
+
+    /// Append the given addresses to `out`, separated by `separator`. Enough space is reserved up
+    /// front, so that `out` is not reallocated while formatting, and can be reused for the next
+    /// batch.
+    ///
+    /// ```rust
+    /// # use ipaddr::IpAddress;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ips = [
+    ///     IpAddress::from_str("192.0.2.1").unwrap(),
+    ///     IpAddress::from_str("2001:db8::1").unwrap(),
+    /// ];
+    /// let mut out = String::new();
+    /// IpAddress::format_into(&ips, &mut out, " ");
+    /// assert_eq!(out, "192.0.2.1 2001:db8::1");
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn format_into(addresses: &[Self], out: &mut String, separator: &str) {
+        out.reserve(addresses.len() * (IPV6_MAX_LEN + separator.len()));
+        for (i, ip) in addresses.iter().enumerate() {
+            if i > 0 {
+                out.push_str(separator);
+            }
+            match *ip {
+                IpAddress::V4(ip) => out.push_str(&ip.to_str_buf()),
+                IpAddress::V6(ip) => out.push_str(&ip.to_str_buf()),
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use ParseErrorReason;
+
+    #[test]
+    fn test_parse_lines() {
+        let text = b"192.0.2.1\n\r\n2001:db8::1\r\n\n192.0.2.2";
+        let lines: Vec<_> = IpAddress::parse_lines(text).collect();
+        let numbers: Vec<_> = lines.iter().map(|(line, _)| *line).collect();
+        assert_eq!(numbers, vec![1, 2, 3, 4, 5]);
+        assert_eq!(lines[1].1.as_ref().unwrap_err().error().offset(), 0);
+        assert_eq!(
+            lines[1].1.as_ref().unwrap_err().error().reason(),
+            ParseErrorReason::Empty
+        );
+        assert!(lines[2].1.as_ref().unwrap().is_ipv6());
+        assert!(lines[4].1.as_ref().unwrap().is_ipv4());
+
+        let lines: Vec<_> = IpAddress::parse_lines(text).skip_empty(true).collect();
+        let numbers: Vec<_> = lines.iter().map(|(line, _)| *line).collect();
+        assert_eq!(numbers, vec![1, 3, 5]);
+        assert!(lines.iter().all(|(_, result)| result.is_ok()));
+
+        assert_eq!(Ipv4Address::parse_lines(b"").count(), 0);
+        assert_eq!(Ipv4Address::parse_lines(b"\n").count(), 1);
+        assert_eq!(Ipv4Address::parse_lines(b"\n").skip_empty(true).count(), 0);
+    }
+
+    #[test]
+    fn test_format_round_trip() {
+        let ips: Vec<Ipv6Address> = (0..100u128)
+            .map(|i| {
+                Ipv6Address(i.wrapping_mul(0x9e37_79b9_7f4a_7c15_f39c_c060_5ced_c835) >> (i % 128))
+            })
+            .collect();
+        let mut out = String::new();
+        Ipv6Address::format_into(&ips, &mut out, "\n");
+        let capacity = out.capacity();
+        let parsed: Vec<_> = Ipv6Address::parse_lines(out.as_bytes())
+            .map(|(_, result)| result.unwrap())
+            .collect();
+        assert_eq!(parsed, ips);
+
+        // The buffer is reused
+        out.clear();
+        Ipv6Address::format_into(&ips, &mut out, "\n");
+        assert_eq!(out.capacity(), capacity);
+
+        let strings: Vec<String> = ips.iter().map(|ip| ip.to_string()).collect();
+        let parsed: Vec<_> = Ipv6Address::parse_many(&strings)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(parsed, ips);
+    }
+}
@@ -0,0 +1,601 @@
+//! Conformance tests of the textual representations of addresses, against:
+//!
+//! - the examples of [RFC4291 section 2.2](https://tools.ietf.org/html/rfc4291#section-2.2)
+//! - the canonical representations of [RFC5952](https://tools.ietf.org/html/rfc5952)
+//! - the host parser and serializer of the [WHATWG URL Standard](https://url.spec.whatwg.org/#hosts-(domains-and-ip-addresses)),
+//!   with cases from the web-platform-tests URL test data
+//! - `std::net::Ipv4Addr` and `std::net::Ipv6Addr`, for every case
+//!
+//! The intentional divergences from `std::net` are:
+//!
+//! - `from_str()` accepts IPv4 octets with leading zeros, which std rejects because some stacks
+//!   read them as octal numbers. `ParseOptions::strict()` rejects them too. This also applies to
+//!   IPv4 addresses embedded in IPv6 addresses.
+//! - IPv4-mapped IPv6 addresses are formatted with hextets by default (`::ffff:c000:221`), while
+//!   std writes the last 32 bits as an IPv4 address (`::ffff:192.0.2.33`). Use
+//!   `Ipv4Suffix::Auto` or `Ipv4Suffix::Always` to get the std representation.
+
+use core::str::FromStr;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use {Ipv4Address, Ipv4Suffix, Ipv6Address, ParseOptions};
+
+/// IPv4 addresses, and the value `Ipv4Address::from_str()` returns.
+const IPV4_CASES: &[(&str, Option<u32>)] = &[
+    ("0.0.0.0", Some(0)),
+    ("255.255.255.255", Some(0xffff_ffff)),
+    ("127.0.0.1", Some(0x7f00_0001)),
+    ("192.0.2.33", Some(0xc000_0221)),
+    ("10.1.0.1", Some(0x0a01_0001)),
+    ("1.2.3.4", Some(0x0102_0304)),
+    ("224.0.0.251", Some(0xe000_00fb)),
+    ("01.2.3.4", Some(0x0102_0304)),
+    ("1.2.3.004", Some(0x0102_0304)),
+    ("00.0.0.0", Some(0)),
+    ("010.010.010.010", Some(0x0a0a_0a0a)),
+    ("255.255.255.0255", None),
+    ("1.2.3.0004", None),
+    ("256.0.0.1", None),
+    ("1.256.0.1", None),
+    ("1.2.3.256", None),
+    ("999.0.0.1", None),
+    ("1.2.3", None),
+    ("1.2", None),
+    ("1", None),
+    ("1.2.3.4.5", None),
+    ("1.2.3.4.", None),
+    (".1.2.3.4", None),
+    ("1..2.3", None),
+    ("1.2..3.4", None),
+    ("", None),
+    (".", None),
+    ("...", None),
+    ("....", None),
+    ("1.2.3.-4", None),
+    ("+1.2.3.4", None),
+    ("1.2.3.+4", None),
+    (" 1.2.3.4", None),
+    ("1.2.3.4 ", None),
+    ("1.2.3.4\n", None),
+    ("1.2. 3.4", None),
+    ("0x1.2.3.4", None),
+    ("0x7f000001", None),
+    ("2130706433", None),
+    ("1e1.2.3.4", None),
+    ("1.2.3.4/24", None),
+    ("1.2.3.4:80", None),
+    ("1.2.3.a", None),
+    ("\u{ff11}.2.3.4", None),
+    ("::1.2.3.4", None),
+];
+
+/// The inputs of `IPV4_CASES` that `Ipv4Addr::from_str()` rejects.
+const IPV4_STD_DIVERGENCES: &[&str] = &["01.2.3.4", "1.2.3.004", "00.0.0.0", "010.010.010.010"];
+
+/// IPv6 addresses, and the value `Ipv6Address::from_str()` returns. This starts with the examples
+/// of RFC4291 section 2.2.
+const IPV6_CASES: &[(&str, Option<u128>)] = &[
+    // RFC4291 section 2.2, form 1
+    (
+        "ABCD:EF01:2345:6789:ABCD:EF01:2345:6789",
+        Some(0xabcd_ef01_2345_6789_abcd_ef01_2345_6789),
+    ),
+    (
+        "2001:DB8:0:0:8:800:200C:417A",
+        Some(0x2001_0db8_0000_0000_0008_0800_200c_417a),
+    ),
+    // RFC4291 section 2.2, form 2
+    (
+        "2001:DB8::8:800:200C:417A",
+        Some(0x2001_0db8_0000_0000_0008_0800_200c_417a),
+    ),
+    (
+        "FF01:0:0:0:0:0:0:101",
+        Some(0xff01_0000_0000_0000_0000_0000_0000_0101),
+    ),
+    ("FF01::101", Some(0xff01_0000_0000_0000_0000_0000_0000_0101)),
+    ("0:0:0:0:0:0:0:1", Some(1)),
+    ("::1", Some(1)),
+    ("0:0:0:0:0:0:0:0", Some(0)),
+    ("::", Some(0)),
+    // RFC4291 section 2.2, form 3
+    ("0:0:0:0:0:0:13.1.68.3", Some(0x0d01_4403)),
+    ("0:0:0:0:0:FFFF:129.144.52.38", Some(0xffff_8190_3426)),
+    ("::13.1.68.3", Some(0x0d01_4403)),
+    ("::FFFF:129.144.52.38", Some(0xffff_8190_3426)),
+    // Ellipsis at every position, including for a single zero hextet
+    (
+        "::2:3:4:5:6:7:8",
+        Some(0x0000_0002_0003_0004_0005_0006_0007_0008),
+    ),
+    (
+        "1::3:4:5:6:7:8",
+        Some(0x0001_0000_0003_0004_0005_0006_0007_0008),
+    ),
+    (
+        "1:2::4:5:6:7:8",
+        Some(0x0001_0002_0000_0004_0005_0006_0007_0008),
+    ),
+    (
+        "1:2:3:4:5:6::8",
+        Some(0x0001_0002_0003_0004_0005_0006_0000_0008),
+    ),
+    (
+        "1:2:3:4:5:6:7::",
+        Some(0x0001_0002_0003_0004_0005_0006_0007_0000),
+    ),
+    ("1::", Some(0x0001_0000_0000_0000_0000_0000_0000_0000)),
+    ("::ffff", Some(0xffff)),
+    ("0::0", Some(0)),
+    ("0:0::0:0", Some(0)),
+    // Leading zeros and case
+    ("0000:0000:0000:0000:0000:0000:0000:0001", Some(1)),
+    (
+        "2001:0db8::0001",
+        Some(0x2001_0db8_0000_0000_0000_0000_0000_0001),
+    ),
+    (
+        "2001:DB8::aBcD",
+        Some(0x2001_0db8_0000_0000_0000_0000_0000_abcd),
+    ),
+    ("FE80::1", Some(0xfe80_0000_0000_0000_0000_0000_0000_0001)),
+    // Embedded IPv4 addresses
+    ("::1.2.3.4", Some(0x0102_0304)),
+    ("::0.0.0.0", Some(0)),
+    ("::ffff:1.2.3.4", Some(0xffff_0102_0304)),
+    ("::ffff:0.0.0.0", Some(0xffff_0000_0000)),
+    (
+        "64:ff9b::192.0.2.33",
+        Some(0x0064_ff9b_0000_0000_0000_0000_c000_0221),
+    ),
+    (
+        "1:2:3:4:5:6:1.2.3.4",
+        Some(0x0001_0002_0003_0004_0005_0006_0102_0304),
+    ),
+    (
+        "1:2:3:4:5::1.2.3.4",
+        Some(0x0001_0002_0003_0004_0005_0000_0102_0304),
+    ),
+    (
+        "1::1.2.3.4",
+        Some(0x0001_0000_0000_0000_0000_0000_0102_0304),
+    ),
+    ("::1.02.3.4", Some(0x0102_0304)),
+    ("::01.2.3.4", Some(0x0102_0304)),
+    ("::1.2.3.04", Some(0x0102_0304)),
+    // Invalid: wrong number of hextets
+    ("1:2:3:4:5:6:7", None),
+    ("1:2:3:4:5:6:7:8:9", None),
+    ("0:0:0:0:0:0:0:0:0", None),
+    ("1::2:3:4:5:6:7:8", None),
+    ("::1:2:3:4:5:6:7:8", None),
+    ("1:2:3:4:5:6:7:8::", None),
+    ("1:2:3:4:5:6:7:1.2.3.4", None),
+    ("1:2:3:4:5:6::1.2.3.4", None),
+    ("1:2:3:4:5:1.2.3.4", None),
+    // Invalid: misplaced colons
+    ("", None),
+    (":", None),
+    (":::", None),
+    ("1:", None),
+    (":1", None),
+    ("1:2:3:4:5:6:7:", None),
+    (":1:2:3:4:5:6:7", None),
+    (":1::", None),
+    ("::1:", None),
+    ("::0:", None),
+    ("1::2::3", None),
+    ("::1::", None),
+    ("1:::2", None),
+    // Invalid: hextets
+    ("00000::1", None),
+    ("0::00001", None),
+    ("12345::", None),
+    ("g::1", None),
+    ("::1g", None),
+    ("-1::", None),
+    ("+1::", None),
+    ("0x1::", None),
+    // Invalid: embedded IPv4 addresses
+    ("::1.2.3", None),
+    ("::1.2.3.", None),
+    ("::1.2.3.4.5", None),
+    ("::ffff:256.0.0.1", None),
+    ("::1.2.3.4:5", None),
+    ("::1.2.3.4x", None),
+    ("::1.2.3.0004", None),
+    ("1.2.3.4::", None),
+    ("1.2.3.4", None),
+    ("::1.2.3.4::", None),
+    // Invalid: syntaxes that are only accepted with `ParseOptions`
+    ("[::1]", None),
+    ("fe80::1%eth0", None),
+    ("fe80::1%", None),
+    (" ::1", None),
+    ("::1 ", None),
+    ("::1/128", None),
+];
+
+/// The inputs of `IPV6_CASES` that `Ipv6Addr::from_str()` rejects.
+const IPV6_STD_DIVERGENCES: &[&str] = &["::1.02.3.4", "::01.2.3.4", "::1.2.3.04"];
+
+/// IPv6 addresses, and their canonical representation as described by RFC5952. Most cases come
+/// from the RFC.
+const RFC_5952_CASES: &[(&str, &str)] = &[
+    // Section 2.1
+    ("2001:db8:0:0:1:0:0:1", "2001:db8::1:0:0:1"),
+    ("2001:0db8:0:0:1:0:0:1", "2001:db8::1:0:0:1"),
+    ("2001:db8::1:0:0:1", "2001:db8::1:0:0:1"),
+    ("2001:db8::0:1:0:0:1", "2001:db8::1:0:0:1"),
+    ("2001:0db8::1:0:0:1", "2001:db8::1:0:0:1"),
+    ("2001:db8:0:0:1::1", "2001:db8::1:0:0:1"),
+    ("2001:db8:0000:0:1::1", "2001:db8::1:0:0:1"),
+    ("2001:DB8:0:0:1::1", "2001:db8::1:0:0:1"),
+    // Section 2.2
+    (
+        "2001:db8:aaaa:bbbb:cccc:dddd:eeee:0001",
+        "2001:db8:aaaa:bbbb:cccc:dddd:eeee:1",
+    ),
+    (
+        "2001:db8:aaaa:bbbb:cccc:dddd:eeee:001",
+        "2001:db8:aaaa:bbbb:cccc:dddd:eeee:1",
+    ),
+    (
+        "2001:db8:aaaa:bbbb:cccc:dddd:eeee:01",
+        "2001:db8:aaaa:bbbb:cccc:dddd:eeee:1",
+    ),
+    (
+        "2001:db8:aaaa:bbbb:cccc:dddd:eeee:1",
+        "2001:db8:aaaa:bbbb:cccc:dddd:eeee:1",
+    ),
+    // Section 2.3
+    (
+        "2001:db8:aaaa:bbbb:cccc:dddd::1",
+        "2001:db8:aaaa:bbbb:cccc:dddd:0:1",
+    ),
+    (
+        "2001:db8:aaaa:bbbb:cccc:dddd:0:1",
+        "2001:db8:aaaa:bbbb:cccc:dddd:0:1",
+    ),
+    ("2001:db8:0:0:0::1", "2001:db8::1"),
+    ("2001:db8:0:0::1", "2001:db8::1"),
+    ("2001:db8:0::1", "2001:db8::1"),
+    ("2001:db8::1", "2001:db8::1"),
+    ("2001:db8::aaaa:0:0:1", "2001:db8::aaaa:0:0:1"),
+    ("2001:db8:0:0:aaaa::1", "2001:db8::aaaa:0:0:1"),
+    // Section 2.4
+    (
+        "2001:db8:aaaa:bbbb:cccc:dddd:eeee:aaaa",
+        "2001:db8:aaaa:bbbb:cccc:dddd:eeee:aaaa",
+    ),
+    (
+        "2001:db8:aaaa:bbbb:cccc:dddd:eeee:AAAA",
+        "2001:db8:aaaa:bbbb:cccc:dddd:eeee:aaaa",
+    ),
+    (
+        "2001:db8:aaaa:bbbb:cccc:dddd:eeee:AaAa",
+        "2001:db8:aaaa:bbbb:cccc:dddd:eeee:aaaa",
+    ),
+    // Section 4.1: leading zeros are suppressed
+    ("2001:0db8::0001", "2001:db8::1"),
+    ("2001:db8:0:0:0:0:2:1", "2001:db8::2:1"),
+    // Section 4.2.1: the longest sequence of zeros is shortened
+    ("2001:db8:0:0:0:0:0:1", "2001:db8::1"),
+    // Section 4.2.2: a single zero hextet is not shortened
+    ("2001:db8:0:1:1:1:1:1", "2001:db8:0:1:1:1:1:1"),
+    ("2001:db8::1:1:1:1:1", "2001:db8:0:1:1:1:1:1"),
+    // Section 4.2.3: the longest sequence wins, or the first one on ties
+    ("2001:0:0:1:0:0:0:1", "2001:0:0:1::1"),
+    ("2001:db8:0:0:1:0:0:1", "2001:db8::1:0:0:1"),
+    ("1:0:0:2:0:0:3:4", "1::2:0:0:3:4"),
+    ("1:0:0:1:0:0:0:0", "1:0:0:1::"),
+    ("0:0:1:0:0:0:1:0", "0:0:1::1:0"),
+    ("1:0:1:0:1:0:1:0", "1:0:1:0:1:0:1:0"),
+    ("0:1:0:1:0:1:0:1", "0:1:0:1:0:1:0:1"),
+    // Section 4.3: lower case
+    ("2001:DB8::ABCD", "2001:db8::abcd"),
+    ("FE80::8657:E6FE:8D5:5325", "fe80::8657:e6fe:8d5:5325"),
+    // Other edge cases
+    ("::", "::"),
+    ("::1", "::1"),
+    ("1::", "1::"),
+    ("0:0:0:0:0:0:0:0", "::"),
+    ("1:2:3:4:5:6:7:0", "1:2:3:4:5:6:7:0"),
+    ("0:2:3:4:5:6:7:8", "0:2:3:4:5:6:7:8"),
+    ("1:0:0:0:0:0:0:8", "1::8"),
+    (
+        "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff",
+        "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff",
+    ),
+];
+
+/// IPv6 addresses, and their representation with the IPv4 suffixes recommended by RFC5952
+/// section 5 (`Ipv4Suffix::Auto`).
+const RFC_5952_IPV4_SUFFIX_CASES: &[(&str, &str)] = &[
+    ("::ffff:192.0.2.1", "::ffff:192.0.2.1"),
+    ("::ffff:c000:201", "::ffff:192.0.2.1"),
+    ("::ffff:0:0", "::ffff:0.0.0.0"),
+    ("64:ff9b::c000:221", "64:ff9b::192.0.2.33"),
+    ("::c000:221", "::192.0.2.33"),
+    ("::1", "::1"),
+    ("::", "::"),
+    ("2001:db8::c000:221", "2001:db8::c000:221"),
+];
+
+/// The content of IPv6 hosts in URLs, and their serialization as described by the WHATWG URL
+/// Standard. This is the `Ipv4Suffix::Never` formatting, and the embedded IPv4 addresses must not
+/// have leading zeros (`ParseOptions::strict()`).
+const WHATWG_IPV6_CASES: &[(&str, Option<&str>)] = &[
+    ("[::]", Some("[::]")),
+    ("[0::0]", Some("[::]")),
+    ("[::1]", Some("[::1]")),
+    ("[0:0::1]", Some("[::1]")),
+    ("[1:0::]", Some("[1::]")),
+    ("[0:0:0:0:0:0:0:1]", Some("[::1]")),
+    ("[1:2:3:4:5:6:7:8]", Some("[1:2:3:4:5:6:7:8]")),
+    ("[1::2:0:0:3]", Some("[1::2:0:0:3]")),
+    ("[0:1:0:1:0:1:0:1]", Some("[0:1:0:1:0:1:0:1]")),
+    ("[1:0:1:0:1:0:1:0]", Some("[1:0:1:0:1:0:1:0]")),
+    ("[2001:DB8::1]", Some("[2001:db8::1]")),
+    ("[0:0:0:0:0:0:13.1.68.3]", Some("[::d01:4403]")),
+    ("[::127.0.0.1]", Some("[::7f00:1]")),
+    ("[::ffff:192.0.2.1]", Some("[::ffff:c000:201]")),
+    ("[::1.2.3.4]", Some("[::102:304]")),
+    ("[::127.0.0.1.]", None),
+    ("[::1.2.3.]", None),
+    ("[::1.2.]", None),
+    ("[::1.]", None),
+    ("[::1.2.3.4x]", None),
+    ("[::1.02.3.4]", None),
+    ("[::1.2.3.256]", None),
+    ("[::1.2.3.4.5]", None),
+    ("[1:2:3:4:5:6:7:8:9]", None),
+    ("[1::2::3]", None),
+    ("[:1]", None),
+    ("[1:]", None),
+    ("[12345::]", None),
+    ("[::%31]", None),
+    ("[%3A%3A]", None),
+    ("[google.com]", None),
+    ("[::1", None),
+    ("::1]", None),
+    ("[]", None),
+];
+
+/// IPv4 hosts in URLs, and the address `Ipv4Address::parse_inet_aton()` returns. The WHATWG URL
+/// Standard follows `inet_aton()`, and the cases where `parse_inet_aton()` differs are listed in
+/// `WHATWG_IPV4_DIVERGENCES`.
+const WHATWG_IPV4_CASES: &[(&str, Option<u32>)] = &[
+    ("127.0.0.1", Some(0x7f00_0001)),
+    ("0x7f.1", Some(0x7f00_0001)),
+    ("0X7F.1", Some(0x7f00_0001)),
+    ("127.1", Some(0x7f00_0001)),
+    ("127.0.1", Some(0x7f00_0001)),
+    ("0177.0.0.1", Some(0x7f00_0001)),
+    ("2130706433", Some(0x7f00_0001)),
+    ("0x7f000001", Some(0x7f00_0001)),
+    ("017700000001", Some(0x7f00_0001)),
+    ("0xc0.0250.01", Some(0xc0a8_0001)),
+    ("192.0x00A80001", Some(0xc0a8_0001)),
+    ("0.0x300", Some(0x0000_0300)),
+    ("4294967295", Some(0xffff_ffff)),
+    ("0xffffffff", Some(0xffff_ffff)),
+    ("1.2.3.004", Some(0x0102_0304)),
+    ("4294967296", None),
+    ("0x100000000", None),
+    ("256.0.0.1", None),
+    ("1.256.0", None),
+    ("1.2.0x10000", None),
+    ("1.0x1000000", None),
+    ("0.0.0.0x100", None),
+    ("1.2.3.4.5", None),
+    ("09.1", None),
+    ("08", None),
+    ("00x7f.1", None),
+    ("0xg", None),
+    ("1.2.3.4.", None),
+    ("0x", None),
+    ("0x.1", None),
+    ("", None),
+];
+
+/// The inputs of `WHATWG_IPV4_CASES` that the WHATWG URL Standard accepts, but that
+/// `parse_inet_aton()` rejects: a trailing dot, and the empty hexadecimal number `0x`.
+const WHATWG_IPV4_DIVERGENCES: &[&str] = &["1.2.3.4.", "0x", "0x.1"];
+
+fn std_ipv4(s: &str) -> Option<u32> {
+    Ipv4Addr::from_str(s).ok().map(u32::from)
+}
+
+fn std_ipv6(s: &str) -> Option<u128> {
+    Ipv6Addr::from_str(s).ok().map(u128::from)
+}
+
+/// Return all the valid IPv6 addresses of the test cases.
+fn ipv6_addresses() -> Vec<Ipv6Address> {
+    IPV6_CASES
+        .iter()
+        .filter_map(|&(_, value)| value.map(Ipv6Address::from))
+        .chain(
+            RFC_5952_CASES
+                .iter()
+                .chain(RFC_5952_IPV4_SUFFIX_CASES)
+                .map(|&(s, _)| Ipv6Address::from_str(s).unwrap()),
+        )
+        .collect()
+}
+
+#[test]
+fn test_ipv4_from_str() {
+    for &(s, expected) in IPV4_CASES {
+        assert_eq!(
+            Ipv4Address::from_str(s).ok().map(u32::from),
+            expected,
+            "{:?}",
+            s
+        );
+        assert_eq!(
+            Ipv4Address::from_ascii(s.as_bytes()).ok().map(u32::from),
+            expected,
+            "{:?}",
+            s
+        );
+    }
+}
+
+#[test]
+fn test_ipv4_std() {
+    for &(s, expected) in IPV4_CASES {
+        let strict = Ipv4Address::parse_with(s, &ParseOptions::strict())
+            .ok()
+            .map(u32::from);
+        assert_eq!(strict, std_ipv4(s), "{:?}", s);
+        if IPV4_STD_DIVERGENCES.contains(&s) {
+            assert!(expected.is_some() && std_ipv4(s).is_none(), "{:?}", s);
+        } else {
+            assert_eq!(expected, std_ipv4(s), "{:?}", s);
+        }
+    }
+    for &(s, _) in IPV4_CASES {
+        if let Ok(ip) = Ipv4Address::from_str(s) {
+            assert_eq!(ip.to_string(), Ipv4Addr::from(u32::from(ip)).to_string());
+        }
+    }
+}
+
+#[test]
+fn test_ipv6_from_str() {
+    for &(s, expected) in IPV6_CASES {
+        assert_eq!(
+            Ipv6Address::from_str(s).ok().map(u128::from),
+            expected,
+            "{:?}",
+            s
+        );
+        assert_eq!(
+            Ipv6Address::from_ascii(s.as_bytes()).ok().map(u128::from),
+            expected,
+            "{:?}",
+            s
+        );
+    }
+}
+
+#[test]
+fn test_ipv6_std() {
+    for &(s, expected) in IPV6_CASES {
+        let strict = Ipv6Address::parse_with(s, &ParseOptions::strict())
+            .ok()
+            .map(u128::from);
+        assert_eq!(strict, std_ipv6(s), "{:?}", s);
+        if IPV6_STD_DIVERGENCES.contains(&s) {
+            assert!(expected.is_some() && std_ipv6(s).is_none(), "{:?}", s);
+        } else {
+            assert_eq!(expected, std_ipv6(s), "{:?}", s);
+        }
+    }
+}
+
+#[test]
+fn test_ipv6_format_std() {
+    // std writes IPv4-mapped addresses with an IPv4 suffix, and everything else as RFC5952
+    // without IPv4 suffix.
+    for ip in ipv6_addresses() {
+        let std_ip = Ipv6Addr::from(u128::from(ip));
+        let mut s = String::new();
+        if ip.is_ipv4_mapped() {
+            ip.formatter(&mut s)
+                .ipv4_suffix(Ipv4Suffix::Always)
+                .write()
+                .unwrap();
+            assert_ne!(ip.to_string(), std_ip.to_string());
+        } else {
+            ip.formatter(&mut s).write().unwrap();
+        }
+        assert_eq!(s, std_ip.to_string());
+    }
+}
+
+#[test]
+fn test_rfc_5952() {
+    for &(s, expected) in RFC_5952_CASES {
+        let ip = Ipv6Address::from_str(s).unwrap();
+        assert_eq!(ip.to_string(), expected, "{:?}", s);
+        assert_eq!(&*ip.to_str_buf(), expected, "{:?}", s);
+        let mut formatted = String::new();
+        ip.formatter(&mut formatted).rfc_5952().write().unwrap();
+        assert_eq!(formatted, expected, "{:?}", s);
+        // The canonical form is stable
+        assert_eq!(Ipv6Address::from_str(expected).unwrap(), ip);
+    }
+    for &(s, expected) in RFC_5952_IPV4_SUFFIX_CASES {
+        let ip = Ipv6Address::from_str(s).unwrap();
+        let mut formatted = String::new();
+        ip.formatter(&mut formatted)
+            .ipv4_suffix(Ipv4Suffix::Auto)
+            .write()
+            .unwrap();
+        assert_eq!(formatted, expected, "{:?}", s);
+        assert_eq!(Ipv6Address::from_str(expected).unwrap(), ip);
+    }
+}
+
+#[test]
+fn test_ipv6_formatter_round_trip() {
+    let suffixes = [Ipv4Suffix::Never, Ipv4Suffix::Always, Ipv4Suffix::Auto];
+    for ip in ipv6_addresses() {
+        for &ellipsis in &[false, true] {
+            for &leading_zeros in &[false, true] {
+                for &upper_case in &[false, true] {
+                    for &suffix in &suffixes {
+                        let mut s = String::new();
+                        ip.formatter(&mut s)
+                            .ellipsis(ellipsis)
+                            .leading_zeros(leading_zeros)
+                            .upper_case(upper_case)
+                            .ipv4_suffix(suffix)
+                            .write()
+                            .unwrap();
+                        assert_eq!(Ipv6Address::from_str(&s).unwrap(), ip, "{:?}", s);
+                        assert_eq!(std_ipv6(&s), Some(u128::from(ip)), "{:?}", s);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_whatwg_ipv6() {
+    let options = ParseOptions::strict().brackets(true);
+    for &(s, expected) in WHATWG_IPV6_CASES {
+        // The WHATWG parser requires the brackets
+        let result = if s.starts_with('[') && s.ends_with(']') {
+            Ipv6Address::parse_with(s, &options)
+                .ok()
+                .map(|ip| format!("[{}]", ip))
+        } else {
+            None
+        };
+        assert_eq!(result.as_deref(), expected, "{:?}", s);
+    }
+}
+
+#[test]
+fn test_whatwg_ipv4() {
+    for &(s, expected) in WHATWG_IPV4_CASES {
+        let result = Ipv4Address::parse_inet_aton(s)
+            .ok()
+            .map(|(ip, _)| u32::from(ip));
+        assert_eq!(result, expected, "{:?}", s);
+        if WHATWG_IPV4_DIVERGENCES.contains(&s) {
+            assert!(result.is_none());
+        }
+        // Without legacy forms, this is the same as from_str()
+        if let Ok((ip, form)) = Ipv4Address::parse_inet_aton(s) {
+            if !form.is_legacy() {
+                assert_eq!(Ipv4Address::from_str(s).unwrap(), ip, "{:?}", s);
+            }
+        }
+    }
+}
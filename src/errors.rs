@@ -1,5 +1,8 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::fmt;
 
 #[derive(Debug)]
 pub struct MalformedAddress;
@@ -9,6 +12,7 @@ impl fmt::Display for MalformedAddress {
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for MalformedAddress {
     fn description(&self) -> &str {
         "malformed IP address"
@@ -19,24 +23,222 @@ impl Error for MalformedAddress {
     }
 }
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// The reason why a string cannot be parsed into an IP address, a mask or a network.
+pub enum ParseErrorReason {
+    /// The string is empty
+    Empty,
+    /// The string ended before the address was complete
+    UnexpectedEnd,
+    /// A character that is not allowed at this position was found
+    InvalidCharacter,
+    /// A valid address was read, but it is followed by other characters
+    TrailingCharacters,
+    /// An IPv4 octet is greater than 255
+    OctetOutOfRange,
+    /// An IPv4 octet has a leading zero, which is not allowed by the
+    /// [`ParseOptions`](struct.ParseOptions.html)
+    LeadingZero,
+    /// An IPv4 octet has more than 3 digits, or an IPv6 hextet has more than 4 digits
+    TooManyDigits,
+    /// An IPv6 address has more than 8 hextets
+    TooManyHextets,
+    /// An IPv6 address has less than 8 hextets, and no `::`
+    TooFewHextets,
+    /// An IPv6 address has more than one `::`
+    DoubleEllipsis,
+    /// An IPv6 address has a `::` that does not stand for any hextet, like in `1:2:3:4::5:6:7:8`
+    EmptyEllipsis,
+    /// An IPv6 address starts or ends with a single `:`
+    SingleColon,
+    /// The IPv4 address embedded in an IPv6 address is invalid
+    InvalidEmbeddedIpv4,
+    /// The mask is not a valid mask
+    InvalidMask,
+    /// The prefix length is too big
+    InvalidPrefixLength,
+    /// A label of a reverse DNS name is invalid
+    InvalidLabel,
+}
+
+impl ParseErrorReason {
+    pub(crate) const fn description(self) -> &'static str {
+        use self::ParseErrorReason::*;
+        match self {
+            Empty => "empty string",
+            UnexpectedEnd => "unexpected end of string",
+            InvalidCharacter => "invalid character",
+            TrailingCharacters => "trailing characters",
+            OctetOutOfRange => "octet out of range",
+            LeadingZero => "leading zero",
+            TooManyDigits => "too many digits",
+            TooManyHextets => "too many hextets",
+            TooFewHextets => "too few hextets",
+            DoubleEllipsis => "more than one \"::\"",
+            EmptyEllipsis => "\"::\" does not stand for any hextet",
+            SingleColon => "single \":\" at the beginning or end of the address",
+            InvalidEmbeddedIpv4 => "invalid embedded IPv4 address",
+            InvalidMask => "invalid mask",
+            InvalidPrefixLength => "invalid prefix length",
+            InvalidLabel => "invalid label",
+        }
+    }
+}
+
+impl fmt::Display for ParseErrorReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// Describe why and where parsing failed.
+pub struct ParseError {
+    offset: usize,
+    reason: ParseErrorReason,
+}
+
+impl ParseError {
+    pub(crate) const fn new(offset: usize, reason: ParseErrorReason) -> Self {
+        ParseError { offset, reason }
+    }
+
+    /// Return the position, in bytes, where parsing failed.
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Return the reason why parsing failed.
+    pub const fn reason(&self) -> ParseErrorReason {
+        self.reason
+    }
+
+    /// Return a new error with the offset shifted by `n` bytes. This is useful when parsing a
+    /// sub-string.
+    pub(crate) const fn shift(self, n: usize) -> Self {
+        ParseError::new(self.offset + n, self.reason)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at offset {}", self.reason, self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ParseError {
+    fn description(&self) -> &str {
+        self.reason.description()
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        None
+    }
+}
+
 /// Error returned when a string cannot be parsed into a valid IPv4 or IPv6 address. It contains
-/// the string that triggered the error.
-pub struct ParsingFailed(pub String);
+/// the string that triggered the error, and a [`ParseError`](struct.ParseError.html) that
+/// describes why and where parsing failed.
+///
+/// ```rust
+/// # use ipaddr::{Ipv6Address, ParseErrorReason};
+/// # use std::str::FromStr;
+/// # fn main() {
+/// let err = Ipv6Address::from_str("fe80::1::2").unwrap_err();
+/// assert_eq!(err.input(), "fe80::1::2");
+/// assert_eq!(err.offset(), 7);
+/// assert_eq!(err.reason(), ParseErrorReason::DoubleEllipsis);
+/// assert_eq!(
+///     err.to_string(),
+///     "malformed address: \"fe80::1::2\": more than one \"::\" at offset 7"
+/// );
+/// # }
+/// ```
+#[derive(Debug)]
+#[cfg(feature = "alloc")]
+pub struct ParsingFailed(pub String, ParseError);
 
+/// Error returned when a string cannot be parsed into a valid IPv4 or IPv6 address. Without the
+/// `alloc` feature, the string that triggered the error is not kept, and the error only contains
+/// a [`ParseError`](struct.ParseError.html) that describes why and where parsing failed.
+#[derive(Debug)]
+#[cfg(not(feature = "alloc"))]
+pub struct ParsingFailed(ParseError);
+
+#[cfg(feature = "alloc")]
+impl ParsingFailed {
+    pub(crate) fn new(input: &str, error: ParseError) -> Self {
+        ParsingFailed(input.into(), error)
+    }
+
+    /// Build an error from an input that may not be valid UTF-8. Invalid sequences are replaced
+    /// by `U+FFFD`.
+    pub(crate) fn from_bytes(input: &[u8], error: ParseError) -> Self {
+        ParsingFailed(String::from_utf8_lossy(input).into_owned(), error)
+    }
+
+    /// Return the string that could not be parsed.
+    pub fn input(&self) -> &str {
+        &self.0
+    }
+
+    /// Return the details about the error.
+    pub fn error(&self) -> ParseError {
+        self.1
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl ParsingFailed {
+    pub(crate) fn new(_input: &str, error: ParseError) -> Self {
+        ParsingFailed(error)
+    }
+
+    pub(crate) fn from_bytes(_input: &[u8], error: ParseError) -> Self {
+        ParsingFailed(error)
+    }
+
+    /// Return the details about the error.
+    pub fn error(&self) -> ParseError {
+        self.0
+    }
+}
+
+impl ParsingFailed {
+    /// Return the position, in bytes, where parsing failed.
+    pub fn offset(&self) -> usize {
+        self.error().offset()
+    }
+
+    /// Return the reason why parsing failed.
+    pub fn reason(&self) -> ParseErrorReason {
+        self.error().reason()
+    }
+}
+
+#[cfg(feature = "alloc")]
 impl fmt::Display for ParsingFailed {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "malformed address: \"{}\"", self.0)
+        write!(f, "malformed address: \"{}\": {}", self.0, self.1)
     }
 }
 
+#[cfg(not(feature = "alloc"))]
+impl fmt::Display for ParsingFailed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "malformed address: {}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
 impl Error for ParsingFailed {
     fn description(&self) -> &str {
         "the string cannot be parsed as an IP address"
     }
 
     fn cause(&self) -> Option<&Error> {
-        None
+        Some(&self.1)
     }
 }
 
@@ -50,6 +252,7 @@ impl fmt::Display for InvalidMask {
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for InvalidMask {
     fn description(&self) -> &str {
         "not a valid mask"
@@ -59,3 +262,46 @@ impl Error for InvalidMask {
         None
     }
 }
+
+#[derive(Debug)]
+/// A network was used as a NAT64 prefix (see [RFC6052](https://tools.ietf.org/html/rfc6052)),
+/// but its prefix length is not 32, 40, 48, 56, 64 or 96.
+pub struct InvalidNat64Prefix;
+
+impl fmt::Display for InvalidNat64Prefix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid NAT64 prefix")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for InvalidNat64Prefix {
+    fn description(&self) -> &str {
+        "not a valid NAT64 prefix length"
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        None
+    }
+}
+
+#[derive(Debug)]
+/// A buffer is too small to hold the bytes that are written into it.
+pub struct BufferTooSmall;
+
+impl fmt::Display for BufferTooSmall {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "buffer too small")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for BufferTooSmall {
+    fn description(&self) -> &str {
+        "the buffer is too small"
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        None
+    }
+}
@@ -0,0 +1,158 @@
+use core::fmt;
+use core::str::FromStr;
+
+use {Ipv4Address, Ipv6Address, ParsingFailed};
+
+/// An IPv4 or an IPv6 address.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum IpAddress {
+    V4(Ipv4Address),
+    V6(Ipv6Address),
+}
+
+impl IpAddress {
+    /// Return `true` if this is an IPv4 address
+    pub fn is_ipv4(&self) -> bool {
+        match *self {
+            IpAddress::V4(_) => true,
+            IpAddress::V6(_) => false,
+        }
+    }
+
+    /// Return `true` if this is an IPv6 address
+    pub fn is_ipv6(&self) -> bool {
+        !self.is_ipv4()
+    }
+
+    /// Return `true` if this address is `0.0.0.0` or `::`
+    pub fn is_unspecified(&self) -> bool {
+        match *self {
+            IpAddress::V4(ip) => ip.is_unspecified(),
+            IpAddress::V6(ip) => ip.is_unspecified(),
+        }
+    }
+
+    /// Return `true` if this address is `127.0.0.1` or `::1`
+    pub fn is_loopback(&self) -> bool {
+        match *self {
+            IpAddress::V4(ip) => ip.is_loopback(),
+            IpAddress::V6(ip) => ip.is_loopback(),
+        }
+    }
+
+    /// Return `true` if this address is a multicast address
+    pub fn is_multicast(&self) -> bool {
+        match *self {
+            IpAddress::V4(ip) => ip.is_multicast(),
+            IpAddress::V6(ip) => ip.is_multicast(),
+        }
+    }
+
+    /// Convert IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`) into IPv4 addresses. Other addresses
+    /// are returned unchanged. See
+    /// [`Ipv6Address::to_canonical()`](struct.Ipv6Address.html#method.to_canonical).
+    ///
+    /// ```rust
+    /// # use ipaddr::{IpAddress, Ipv4Address};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = IpAddress::from_str("::ffff:192.0.2.33").unwrap();
+    /// assert_eq!(ip.to_canonical(), IpAddress::from_str("192.0.2.33").unwrap());
+    /// # }
+    /// ```
+    pub fn to_canonical(&self) -> IpAddress {
+        match *self {
+            IpAddress::V4(_) => *self,
+            IpAddress::V6(ip) => ip.to_canonical(),
+        }
+    }
+
+    /// Parse an IPv4 or an IPv6 address from ASCII bytes. This is the same as `from_str()`, but
+    /// the input does not need to be valid UTF-8.
+    ///
+    /// ```rust
+    /// # use ipaddr::IpAddress;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// assert_eq!(
+    ///     IpAddress::from_ascii(b"fe80::1").unwrap(),
+    ///     IpAddress::from_str("fe80::1").unwrap()
+    /// );
+    /// assert!(IpAddress::from_ascii(b"192.0.2.\xff").is_err());
+    /// # }
+    /// ```
+    pub fn from_ascii(bytes: &[u8]) -> Result<Self, ParsingFailed> {
+        if bytes.contains(&b':') {
+            Ipv6Address::from_ascii(bytes).map(IpAddress::V6)
+        } else {
+            Ipv4Address::from_ascii(bytes).map(IpAddress::V4)
+        }
+    }
+}
+
+impl fmt::Display for IpAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IpAddress::V4(ref ip) => fmt::Display::fmt(ip, f),
+            IpAddress::V6(ref ip) => fmt::Display::fmt(ip, f),
+        }
+    }
+}
+
+impl From<Ipv4Address> for IpAddress {
+    fn from(ip: Ipv4Address) -> Self {
+        IpAddress::V4(ip)
+    }
+}
+
+impl From<Ipv6Address> for IpAddress {
+    fn from(ip: Ipv6Address) -> Self {
+        IpAddress::V6(ip)
+    }
+}
+
+impl FromStr for IpAddress {
+    type Err = ParsingFailed;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // An IPv6 address always contains a column, and an IPv4 address never does
+        if s.contains(':') {
+            Ipv6Address::from_str(s).map(IpAddress::V6)
+        } else {
+            Ipv4Address::from_str(s).map(IpAddress::V4)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            IpAddress::from_str("1.2.3.4").unwrap(),
+            IpAddress::V4(Ipv4Address(0x0102_0304))
+        );
+        assert_eq!(
+            IpAddress::from_str("::1.2.3.4").unwrap(),
+            IpAddress::V6(Ipv6Address(0x0102_0304))
+        );
+        assert!(IpAddress::from_str("1.2.3").is_err());
+        assert!(IpAddress::from_str("::1::").is_err());
+    }
+
+    #[test]
+    fn test_to_canonical() {
+        let ipv4 = Ipv4Address(0xc000_0221);
+        assert_eq!(
+            IpAddress::V6(ipv4.to_ipv6_mapped()).to_canonical(),
+            IpAddress::V4(ipv4)
+        );
+        assert_eq!(
+            IpAddress::V6(ipv4.to_ipv6_compatible()).to_canonical(),
+            IpAddress::V6(ipv4.to_ipv6_compatible())
+        );
+        assert_eq!(IpAddress::V4(ipv4).to_canonical(), IpAddress::V4(ipv4));
+    }
+}
@@ -1,9 +1,19 @@
-use std::convert::TryFrom;
-use std::fmt;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
 
-use {IPV4_LOOPBACK, IPV4_UNSPECIFIED, MalformedAddress};
+use {Ipv4Formatter, Ipv4StrBuf, Ipv6Address, IPV4_LOOPBACK, IPV4_UNSPECIFIED, MalformedAddress};
 
 /// An Ipv4 address
+///
+/// # Compatibility with `std::net::Ipv4Addr`
+///
+/// `from_str()` accepts the same strings as `Ipv4Addr`, except that octets may have leading
+/// zeros, like `192.0.2.033`. They are read as decimal numbers, but `Ipv4Addr` rejects them
+/// because some stacks read them as octal numbers. Use
+/// [`ParseOptions::strict()`](struct.ParseOptions.html#method.strict) to reject them. Formatting
+/// gives the same strings as `Ipv4Addr`.
 #[derive(Copy, Eq, PartialEq, Hash, Clone)]
 pub struct Ipv4Address(pub(crate) u32);
 
@@ -136,6 +146,45 @@ impl Ipv4Address {
         Ok(Self::from_slice_unchecked(bytes))
     }
 
+    /// Convert this address into an IPv4-mapped IPv6 address (`::ffff:a.b.c.d`). See
+    /// [`Ipv6Address::is_ipv4_mapped()`](struct.Ipv6Address.html#method.is_ipv4_mapped).
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv6Address};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = Ipv4Address::from_str("192.0.2.33").unwrap();
+    /// assert_eq!(ip.to_ipv6_mapped(), Ipv6Address::from_str("::ffff:192.0.2.33").unwrap());
+    /// # }
+    /// ```
+    pub fn to_ipv6_mapped(self) -> Ipv6Address {
+        Ipv6Address(0xffff_0000_0000 | u128::from(self.0))
+    }
+
+    /// Convert this address into an IPv4-compatible IPv6 address (`::a.b.c.d`). See
+    /// [`Ipv6Address::is_ipv4_compatible()`](struct.Ipv6Address.html#method.is_ipv4_compatible).
+    ///
+    /// Note that IPv4-compatible addresses are deprecated. Unless you know what you are doing,
+    /// you probably want [`to_ipv6_mapped()`](#method.to_ipv6_mapped) instead.
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv6Address};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = Ipv4Address::from_str("192.0.2.33").unwrap();
+    /// assert_eq!(ip.to_ipv6_compatible(), Ipv6Address::from_str("::192.0.2.33").unwrap());
+    /// # }
+    /// ```
+    pub fn to_ipv6_compatible(self) -> Ipv6Address {
+        Ipv6Address(u128::from(self.0))
+    }
+
+    /// Create a formatter to stringify this IPv4 address.
+    /// See [`Ipv4Formatter<'a, W>`](struct.Ipv4Formatter.html) for more details.
+    pub fn formatter<'a, W: fmt::Write>(self, writer: &'a mut W) -> Ipv4Formatter<'a, W> {
+        Ipv4Formatter::new(writer, self.0)
+    }
+
     /// Return a human readable representation of the IPv4 address.
     ///
     /// ```rust
@@ -146,23 +195,44 @@ impl Ipv4Address {
     /// assert_eq!(ip.to_string(), "1.2.3.4".to_string());
     /// # }
     /// ```
+    #[cfg(feature = "alloc")]
     pub fn to_string(self) -> String {
-        let octets = self.octets();
-        format!("{}.{}.{}.{}", octets[0], octets[1], octets[2], octets[3])
+        String::from(self.to_str_buf().as_str())
+    }
+
+    /// Return a human readable representation of the IPv4 address, in a buffer allocated on the
+    /// stack.
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address};
+    /// # fn main() {
+    /// let ip = Ipv4Address::from(0xffff_ffff);
+    /// assert_eq!(ip.to_str_buf().as_str(), "255.255.255.255");
+    /// # }
+    /// ```
+    pub fn to_str_buf(self) -> Ipv4StrBuf {
+        let mut buf = Ipv4StrBuf::new();
+        // The longest IPv4 address is 15 bytes long, so this cannot fail
+        write!(buf, "{}", self).expect("Ipv4StrBuf is too small?!");
+        buf
     }
 }
 
 impl fmt::Display for Ipv4Address {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str(&self.to_string())
+        // Padding requires knowing the length first, so only buffer when it is requested
+        if f.width().is_none() && f.precision().is_none() {
+            let octets = self.octets();
+            write!(f, "{}.{}.{}.{}", octets[0], octets[1], octets[2], octets[3])
+        } else {
+            f.pad(&self.to_str_buf())
+        }
     }
 }
 
 impl fmt::Debug for Ipv4Address {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str("Ipv4Address(")?;
-        f.write_str(&self.to_string())?;
-        f.write_str(")")
+        write!(f, "Ipv4Address({})", self)
     }
 }
 
@@ -178,3 +248,34 @@ impl From<[u8; 16]> for Ipv4Address {
         Ipv4Address::from_slice_unchecked(&bytes[..])
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_str_buf() {
+        for ip in &[0, 0x0102_0304, 0xc000_0221, 0xffff_ffff] {
+            let ip = Ipv4Address(*ip);
+            assert_eq!(ip.to_str_buf().as_str(), ip.to_string());
+            assert_eq!(format!("{}", ip), ip.to_string());
+        }
+        assert_eq!(
+            format!("{:?}", Ipv4Address(0xffff_ffff)),
+            "Ipv4Address(255.255.255.255)"
+        );
+    }
+
+    #[test]
+    fn test_display_flags() {
+        let ip = Ipv4Address(0x0a00_0001);
+        assert_eq!(format!("[{:>12}]", ip), "[    10.0.0.1]");
+        assert_eq!(format!("[{:<12}]", ip), "[10.0.0.1    ]");
+        assert_eq!(format!("[{:*^12}]", ip), "[**10.0.0.1**]");
+        assert_eq!(format!("[{:4}]", ip), "[10.0.0.1]");
+        assert_eq!(format!("{:o} {:#x}", ip, ip), "1200000001 0xa000001");
+        let mask = ::Ipv4Mask::from_prefixlen(8).unwrap();
+        assert_eq!(format!("[{:>12}]", mask), "[   255.0.0.0]");
+        assert_eq!(format!("{:#o}", mask), "0o37700000000");
+    }
+}
@@ -0,0 +1,393 @@
+use core::fmt::{Alignment, Result, Write};
+
+use {Ipv4Address, StrBuf};
+
+/// The notation an [`Ipv4Formatter`](struct.Ipv4Formatter.html) uses to write an address.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Ipv4Notation {
+    /// Dotted-quad notation, like `10.0.0.1` (this is the default)
+    DottedDecimal,
+    /// A 32 bits hexadecimal number, like `0x0a000001`. Leading zeros are always written.
+    Hexadecimal,
+    /// A 32 bits decimal number, like `167772161`
+    Integer,
+    /// A 32 bits octal number, like `001200000001`. Leading zeros are always written, and the
+    /// number starts with a `0` as in C, so that it can be read back by `inet_aton()`.
+    Octal,
+    /// A 32 bits binary number, like `0b00001010000000000000000000000001`. Leading zeros are always
+    /// written.
+    Binary,
+    /// Each octet as an 8 bits binary number, like `00001010.00000000.00000000.00000001`. This is
+    /// mostly useful for masks.
+    DottedBinary,
+}
+
+/// Configurable IPv4 formatter. By default, this formatter writes addresses in dotted-quad
+/// notation, like [`to_string()`](struct.Ipv4Address.html#method.to_string).
+///
+/// `Ipv4Formatter` can be obtained from
+/// [`Ipv4Address.formatter()`](struct.Ipv4Address.html#method.formatter) and
+/// [`Ipv4Mask.formatter()`](struct.Ipv4Mask.html#method.formatter).
+///
+/// As for [`Ipv6Formatter`](struct.Ipv6Formatter.html), the [`write()`](#method.write) method
+/// can only fail if writing in the underlying buffer fails.
+///
+/// # Examples
+///
+/// ```rust
+/// # use ipaddr::{Ipv4Address, Ipv4Notation};
+/// # use std::fmt::Alignment;
+/// # fn main() {
+/// let ip = Ipv4Address::from(0x0a01_0001);
+/// let mut s = String::with_capacity(34);
+///
+/// // Default formatting
+/// ip.formatter(&mut s).write().unwrap();
+/// assert_eq!(s, "10.1.0.1");
+///
+/// // Zero-padded octets: the addresses sort lexically in the same order as numerically
+/// s.truncate(0);
+/// ip.formatter(&mut s).leading_zeros(true).write().unwrap();
+/// assert_eq!(s, "010.001.000.001");
+///
+/// // Other notations
+/// s.truncate(0);
+/// ip.formatter(&mut s).notation(Ipv4Notation::Hexadecimal).write().unwrap();
+/// assert_eq!(s, "0x0a010001");
+///
+/// s.truncate(0);
+/// ip.formatter(&mut s).notation(Ipv4Notation::Integer).write().unwrap();
+/// assert_eq!(s, "167837697");
+///
+/// s.truncate(0);
+/// ip.formatter(&mut s).notation(Ipv4Notation::Octal).write().unwrap();
+/// assert_eq!(s, "001200200001");
+///
+/// s.truncate(0);
+/// ip.formatter(&mut s).notation(Ipv4Notation::Binary).write().unwrap();
+/// assert_eq!(s, "0b00001010000000010000000000000001");
+///
+/// // Fixed-width columns
+/// s.truncate(0);
+/// ip.formatter(&mut s).width(15).align(Alignment::Right).write().unwrap();
+/// assert_eq!(s, "       10.1.0.1");
+/// # }
+/// ```
+pub struct Ipv4Formatter<'writer, W: 'writer> {
+    notation: Ipv4Notation,
+    leading_zeros: bool,
+    upper_case: bool,
+    width: usize,
+    align: Alignment,
+    fill: char,
+    writer: &'writer mut W,
+    value: u32,
+}
+
+/// Large enough for the longest notation: 32 digits and 3 dots.
+type Ipv4FormatterBuf = StrBuf<35>;
+
+impl<'writer, W> Ipv4Formatter<'writer, W>
+where
+    W: Write,
+{
+    pub(crate) fn new(writer: &'writer mut W, value: u32) -> Self {
+        Ipv4Formatter {
+            notation: Ipv4Notation::DottedDecimal,
+            leading_zeros: false,
+            upper_case: false,
+            width: 0,
+            align: Alignment::Left,
+            fill: ' ',
+            writer,
+            value,
+        }
+    }
+
+    /// Set the notation to use. By default, this is
+    /// [`Ipv4Notation::DottedDecimal`](enum.Ipv4Notation.html).
+    pub fn notation(mut self, notation: Ipv4Notation) -> Self {
+        self.notation = notation;
+        self
+    }
+
+    /// Set whether the octets of the dotted-quad notation should be padded with zeros to three
+    /// digits, and the integer notation to ten digits. This is `false` by default. The
+    /// hexadecimal, octal and binary notations always have leading zeros.
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv4Notation};
+    /// # fn main() {
+    /// let ip = Ipv4Address::from(0x0a00_0001);
+    /// let mut s = String::with_capacity(15);
+    /// ip.formatter(&mut s).leading_zeros(true).write().unwrap();
+    /// assert_eq!(s, "010.000.000.001");
+    ///
+    /// s.truncate(0);
+    /// ip.formatter(&mut s)
+    ///     .notation(Ipv4Notation::Integer)
+    ///     .leading_zeros(true)
+    ///     .write()
+    ///     .unwrap();
+    /// assert_eq!(s, "0167772161");
+    /// # }
+    /// ```
+    pub fn leading_zeros(mut self, flag: bool) -> Self {
+        self.leading_zeros = flag;
+        self
+    }
+
+    /// Use upper case letters for the hexadecimal notation. The `0x` prefix is not affected. This
+    /// is `false` by default.
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv4Notation};
+    /// # fn main() {
+    /// let ip = Ipv4Address::from(0xc000_02ff);
+    /// let mut s = String::with_capacity(10);
+    /// ip.formatter(&mut s)
+    ///     .notation(Ipv4Notation::Hexadecimal)
+    ///     .upper_case(true)
+    ///     .write()
+    ///     .unwrap();
+    /// assert_eq!(s, "0xC00002FF");
+    /// # }
+    /// ```
+    pub fn upper_case(mut self, flag: bool) -> Self {
+        self.upper_case = flag;
+        self
+    }
+
+    /// Set the minimum number of characters to write. Shorter representations are padded with
+    /// the [`fill`](#method.fill) character, according to the [`align`](#method.align)ment. This
+    /// is `0` by default, which disables padding.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set how the address is aligned when it is shorter than the [`width`](#method.width). This
+    /// is `Alignment::Left` by default.
+    pub fn align(mut self, align: Alignment) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Set the character used to pad the address up to the [`width`](#method.width). This is a
+    /// space by default.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Address;
+    /// # use std::fmt::Alignment;
+    /// # fn main() {
+    /// let ip = Ipv4Address::from(0x7f00_0001);
+    /// let mut s = String::with_capacity(15);
+    /// ip.formatter(&mut s).width(13).align(Alignment::Center).fill('*').write().unwrap();
+    /// assert_eq!(s, "**127.0.0.1**");
+    /// # }
+    /// ```
+    pub fn fill(mut self, fill: char) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    /// Write a string with the current configuration.
+    ///
+    /// The method can only return an error if the writer `W` fails. That means if the
+    /// writer cannot fail (for instance if the writer is a `String`), this method won't fail.
+    pub fn write(&mut self) -> Result {
+        if self.width == 0 {
+            return self.write_address();
+        }
+        let mut buf = Ipv4FormatterBuf::new();
+        Ipv4Formatter::new(&mut buf, self.value)
+            .notation(self.notation)
+            .leading_zeros(self.leading_zeros)
+            .upper_case(self.upper_case)
+            .write_address()?;
+        // All the notations are ASCII, so the length in bytes is the number of characters
+        let padding = self.width.saturating_sub(buf.len());
+        let (before, after) = match self.align {
+            Alignment::Left => (0, padding),
+            Alignment::Right => (padding, 0),
+            Alignment::Center => (padding / 2, padding - padding / 2),
+        };
+        self.write_fill(before)?;
+        self.writer.write_str(buf.as_str())?;
+        self.write_fill(after)
+    }
+
+    fn write_fill(&mut self, count: usize) -> Result {
+        for _ in 0..count {
+            self.writer.write_char(self.fill)?;
+        }
+        Ok(())
+    }
+
+    fn write_address(&mut self) -> Result {
+        let value = self.value;
+        match self.notation {
+            Ipv4Notation::DottedDecimal => {
+                let o = Ipv4Address(value).octets();
+                if self.leading_zeros {
+                    write!(
+                        self.writer,
+                        "{:03}.{:03}.{:03}.{:03}",
+                        o[0], o[1], o[2], o[3]
+                    )
+                } else {
+                    write!(self.writer, "{}.{}.{}.{}", o[0], o[1], o[2], o[3])
+                }
+            }
+            Ipv4Notation::Hexadecimal if self.upper_case => write!(self.writer, "0x{:08X}", value),
+            Ipv4Notation::Hexadecimal => write!(self.writer, "0x{:08x}", value),
+            Ipv4Notation::Integer if self.leading_zeros => write!(self.writer, "{:010}", value),
+            Ipv4Notation::Integer => write!(self.writer, "{}", value),
+            Ipv4Notation::Octal => write!(self.writer, "0{:011o}", value),
+            Ipv4Notation::Binary => write!(self.writer, "0b{:032b}", value),
+            Ipv4Notation::DottedBinary => {
+                let o = Ipv4Address(value).octets();
+                write!(
+                    self.writer,
+                    "{:08b}.{:08b}.{:08b}.{:08b}",
+                    o[0], o[1], o[2], o[3]
+                )
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use Ipv4Mask;
+
+    fn format<F>(value: u32, configure: F) -> String
+    where
+        F: Fn(Ipv4Formatter<String>) -> Ipv4Formatter<String>,
+    {
+        let mut s = String::new();
+        configure(Ipv4Address(value).formatter(&mut s))
+            .write()
+            .unwrap();
+        s
+    }
+
+    #[test]
+    fn test_format_notations() {
+        let cases: &[(u32, &str, &str, &str, &str)] = &[
+            (0, "0.0.0.0", "0x00000000", "0", "000000000000"),
+            (
+                0x0a00_0001,
+                "10.0.0.1",
+                "0x0a000001",
+                "167772161",
+                "001200000001",
+            ),
+            (
+                0xffff_ffff,
+                "255.255.255.255",
+                "0xffffffff",
+                "4294967295",
+                "037777777777",
+            ),
+        ];
+        for &(value, dotted, hex, int, oct) in cases {
+            assert_eq!(format(value, |f| f), dotted);
+            assert_eq!(
+                format(value, |f| f.notation(Ipv4Notation::Hexadecimal)),
+                hex
+            );
+            assert_eq!(format(value, |f| f.notation(Ipv4Notation::Integer)), int);
+            assert_eq!(format(value, |f| f.notation(Ipv4Notation::Octal)), oct);
+            assert_eq!(
+                Ipv4Address::parse_inet_aton(oct).unwrap().0,
+                Ipv4Address(value)
+            );
+            assert_eq!(
+                Ipv4Address::parse_inet_aton(hex).unwrap().0,
+                Ipv4Address(value)
+            );
+        }
+        assert_eq!(
+            format(0x8000_0001, |f| f.notation(Ipv4Notation::Binary)),
+            "0b10000000000000000000000000000001"
+        );
+    }
+
+    #[test]
+    fn test_format_leading_zeros() {
+        assert_eq!(format(0, |f| f.leading_zeros(true)), "000.000.000.000");
+        assert_eq!(
+            format(0xc000_0221, |f| f.leading_zeros(true)),
+            "192.000.002.033"
+        );
+        assert_eq!(
+            format(1, |f| f.notation(Ipv4Notation::Integer).leading_zeros(true)),
+            "0000000001"
+        );
+        // leading zeros make the dotted-quad notation sortable
+        let mut ips: Vec<String> = [0x0a00_0001, 0x0900_0001, 0x0a00_0002, 0x6400_0000]
+            .iter()
+            .map(|ip| format(*ip, |f| f.leading_zeros(true)))
+            .collect();
+        ips.sort();
+        assert_eq!(
+            ips,
+            [
+                "009.000.000.001",
+                "010.000.000.001",
+                "010.000.000.002",
+                "100.000.000.000"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_alignment() {
+        assert_eq!(format(0x0a00_0001, |f| f.width(12)), "10.0.0.1    ");
+        assert_eq!(
+            format(0x0a00_0001, |f| f.width(12).align(Alignment::Right)),
+            "    10.0.0.1"
+        );
+        assert_eq!(
+            format(0x0a00_0001, |f| f
+                .width(11)
+                .align(Alignment::Center)
+                .fill('-')),
+            "-10.0.0.1--"
+        );
+        // the width is a minimum
+        assert_eq!(format(0x0a00_0001, |f| f.width(3)), "10.0.0.1");
+        assert_eq!(
+            format(1, |f| f
+                .notation(Ipv4Notation::Binary)
+                .width(36)
+                .align(Alignment::Right)),
+            "  0b00000000000000000000000000000001"
+        );
+    }
+
+    #[test]
+    fn test_format_mask() {
+        let mut s = String::new();
+        Ipv4Mask::from_prefixlen(20)
+            .unwrap()
+            .formatter(&mut s)
+            .notation(Ipv4Notation::Hexadecimal)
+            .write()
+            .unwrap();
+        assert_eq!(s, "0xfffff000");
+
+        s.truncate(0);
+        Ipv4Mask::from_prefixlen(20)
+            .unwrap()
+            .formatter(&mut s)
+            .notation(Ipv4Notation::DottedBinary)
+            .width(36)
+            .align(Alignment::Right)
+            .write()
+            .unwrap();
+        assert_eq!(s, " 11111111.11111111.11110000.00000000");
+    }
+}
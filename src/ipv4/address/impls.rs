@@ -1,7 +1,7 @@
-use std::num::ParseIntError;
-use std::ops::*;
+use core::num::ParseIntError;
+use core::ops::*;
 
-use core::fmt::{self, Binary, Formatter, LowerHex, UpperHex};
+use core::fmt::{self, Binary, Formatter, LowerHex, Octal, UpperHex};
 
 use {Ipv4Address, Ipv4Mask};
 
@@ -1634,6 +1634,12 @@ impl Binary for Ipv4Address {
     }
 }
 
+impl Octal for Ipv4Address {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        <u32 as Octal>::fmt(&self.0, f)
+    }
+}
+
 impl From<u32> for Ipv4Address {
     fn from(value: u32) -> Self {
         Ipv4Address(value)
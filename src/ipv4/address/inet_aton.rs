@@ -0,0 +1,249 @@
+//! Lenient IPv4 parsing, compatible with the legacy syntaxes accepted by glibc's `inet_aton`.
+
+use ParseErrorReason::*;
+use {Ipv4Address, ParseError, ParsingFailed};
+
+/// Describe the syntax of a string parsed with
+/// [`Ipv4Address::parse_inet_aton()`](struct.Ipv4Address.html#method.parse_inet_aton).
+///
+/// ```rust
+/// # use ipaddr::Ipv4Address;
+/// # fn main() {
+/// let (_, form) = Ipv4Address::parse_inet_aton("0x7f.1").unwrap();
+/// assert_eq!(form.parts(), 2);
+/// assert!(form.has_hexadecimal());
+/// assert!(!form.has_octal());
+/// assert!(form.is_legacy());
+///
+/// let (_, form) = Ipv4Address::parse_inet_aton("127.0.0.1").unwrap();
+/// assert!(!form.is_legacy());
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct InetAtonForm {
+    parts: usize,
+    hexadecimal: bool,
+    octal: bool,
+}
+
+impl InetAtonForm {
+    /// Return the number of dot-separated parts, between 1 and 4. With less than 4 parts, the
+    /// last part fills all the remaining bytes of the address: `127.1` is `127.0.0.1`, and `10`
+    /// is `0.0.0.10`.
+    pub fn parts(&self) -> usize {
+        self.parts
+    }
+
+    /// Return `true` if at least one part is written in hexadecimal, like `0x7f`.
+    pub fn has_hexadecimal(&self) -> bool {
+        self.hexadecimal
+    }
+
+    /// Return `true` if at least one part is written in octal, i.e. starts with a `0` followed
+    /// by other digits, like `0177`.
+    pub fn has_octal(&self) -> bool {
+        self.octal
+    }
+
+    /// Return `true` if the string is not in the strict dotted-decimal form. Such strings are
+    /// either rejected by [`Ipv4Address::from_str()`](struct.Ipv4Address.html), or interpreted
+    /// differently: `010.0.0.1` is `10.0.0.1` for `from_str()`, but `8.0.0.1` for `inet_aton`.
+    pub fn is_legacy(&self) -> bool {
+        self.parts != 4 || self.hexadecimal || self.octal
+    }
+}
+
+impl Ipv4Address {
+    /// Parse an IPv4 address the way glibc's `inet_aton` does, and return the address along with
+    /// the syntax that was used. In addition to the strict dotted-decimal form, this accepts:
+    ///
+    /// - addresses with 1, 2 or 3 parts, where the last part fills the remaining bytes:
+    ///   `a.b.c` is interpreted as `a.b.0.c` (with `c` on 16 bits), `a.b` as `a.0.0.b` (with `b`
+    ///   on 24 bits), and `a` as a 32 bits integer
+    /// - hexadecimal parts, with a `0x` or `0X` prefix: `0x7f.1`
+    /// - octal parts, with a leading `0`: `0177.0.0.1`
+    ///
+    /// Unlike `inet_aton`, characters after a whitespace are not ignored.
+    ///
+    /// This is meant to reproduce how legacy software interprets an address, or to detect
+    /// addresses that are written in an unusual form. Use
+    /// [`from_str()`](struct.Ipv4Address.html) to parse addresses in the usual form.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let localhost = Ipv4Address::from_str("127.0.0.1").unwrap();
+    /// for s in &["127.1", "0x7f.1", "0177.0.0.1", "2130706433", "0x7f000001"] {
+    ///     let (ip, form) = Ipv4Address::parse_inet_aton(s).unwrap();
+    ///     assert_eq!(ip, localhost);
+    ///     assert!(form.is_legacy());
+    /// }
+    ///
+    /// let (ip, form) = Ipv4Address::parse_inet_aton("10.1").unwrap();
+    /// assert_eq!(ip, Ipv4Address::from_str("10.0.0.1").unwrap());
+    /// assert_eq!(form.parts(), 2);
+    ///
+    /// assert!(Ipv4Address::parse_inet_aton("08.0.0.1").is_err());
+    /// assert!(Ipv4Address::parse_inet_aton("1.2.3.4.5").is_err());
+    /// # }
+    /// ```
+    pub fn parse_inet_aton(s: &str) -> Result<(Ipv4Address, InetAtonForm), ParsingFailed> {
+        parse_inet_aton(s.as_bytes())
+            .map(|(ip, form)| (Ipv4Address(ip), form))
+            .map_err(|e| ParsingFailed::new(s, e))
+    }
+}
+
+fn parse_inet_aton(bytes: &[u8]) -> Result<(u32, InetAtonForm), ParseError> {
+    if bytes.is_empty() {
+        return Err(ParseError::new(0, Empty));
+    }
+
+    let mut form = InetAtonForm {
+        parts: 0,
+        hexadecimal: false,
+        octal: false,
+    };
+    // The value of each part, and the offset where it starts
+    let mut parts: [(u32, usize); 4] = [(0, 0); 4];
+    let mut offset = 0;
+
+    loop {
+        let (bytes_read, value, radix) =
+            read_part(&bytes[offset..]).map_err(|e| e.shift(offset))?;
+        match radix {
+            16 => form.hexadecimal = true,
+            8 => form.octal = true,
+            _ => {}
+        }
+        parts[form.parts] = (value, offset);
+        form.parts += 1;
+        offset += bytes_read;
+
+        if offset == bytes.len() {
+            break;
+        }
+        if bytes[offset] != b'.' {
+            return Err(ParseError::new(offset, InvalidCharacter));
+        }
+        if form.parts == 4 {
+            return Err(ParseError::new(offset, TrailingCharacters));
+        }
+        offset += 1;
+    }
+
+    // All the parts but the last one represent a single byte. The last one fills the remaining
+    // bytes.
+    let (last, last_offset) = parts[form.parts - 1];
+    let last_len = 5 - form.parts;
+    if u64::from(last) >> (8 * last_len) != 0 {
+        return Err(ParseError::new(last_offset, OctetOutOfRange));
+    }
+    let mut address = last;
+    for (i, &(value, offset)) in parts[..form.parts - 1].iter().enumerate() {
+        if value > 0xff {
+            return Err(ParseError::new(offset, OctetOutOfRange));
+        }
+        address |= value << ((3 - i) * 8);
+    }
+    Ok((address, form))
+}
+
+/// Read a number written in decimal, octal (with a leading `0`) or hexadecimal (with a leading
+/// `0x` or `0X`). Return the number of bytes that were read, the value, and the radix.
+fn read_part(bytes: &[u8]) -> Result<(usize, u32, u32), ParseError> {
+    let (prefix_len, radix) = match bytes {
+        [b'0', b'x', ..] | [b'0', b'X', ..] => (2, 16),
+        [b'0', next, ..] if next.is_ascii_digit() => (1, 8),
+        _ => (0, 10),
+    };
+
+    let mut value: u64 = 0;
+    let mut offset = prefix_len;
+    while offset < bytes.len() {
+        let digit = match (bytes[offset] as char).to_digit(16) {
+            Some(digit) if digit < radix => digit,
+            // 8 and 9 are not octal digits, but they're not a separator either
+            Some(digit) if radix == 8 && digit < 10 => {
+                return Err(ParseError::new(offset, InvalidCharacter))
+            }
+            _ => break,
+        };
+        value = value * u64::from(radix) + u64::from(digit);
+        if value > 0xffff_ffff {
+            return Err(ParseError::new(0, OctetOutOfRange));
+        }
+        offset += 1;
+    }
+
+    if offset == prefix_len {
+        return if offset == bytes.len() {
+            Err(ParseError::new(offset, UnexpectedEnd))
+        } else {
+            Err(ParseError::new(offset, InvalidCharacter))
+        };
+    }
+    Ok((offset, value as u32, radix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    fn parse(s: &str) -> (Ipv4Address, usize, bool, bool) {
+        let (ip, form) = Ipv4Address::parse_inet_aton(s).unwrap();
+        (ip, form.parts(), form.has_hexadecimal(), form.has_octal())
+    }
+
+    fn error(s: &str) -> (usize, ::ParseErrorReason) {
+        let err = Ipv4Address::parse_inet_aton(s).unwrap_err();
+        (err.offset(), err.reason())
+    }
+
+    #[test]
+    fn test_parse_inet_aton() {
+        let ip = |s| Ipv4Address::from_str(s).unwrap();
+        assert_eq!(parse("1.2.3.4"), (ip("1.2.3.4"), 4, false, false));
+        assert_eq!(parse("0.0.0.0"), (ip("0.0.0.0"), 4, false, false));
+        assert_eq!(parse("10.1"), (ip("10.0.0.1"), 2, false, false));
+        assert_eq!(parse("127.1"), (ip("127.0.0.1"), 2, false, false));
+        assert_eq!(parse("1.2.772"), (ip("1.2.3.4"), 3, false, false));
+        assert_eq!(parse("1.131844"), (ip("1.2.3.4"), 2, false, false));
+        assert_eq!(parse("16909060"), (ip("1.2.3.4"), 1, false, false));
+        assert_eq!(
+            parse("4294967295"),
+            (ip("255.255.255.255"), 1, false, false)
+        );
+        assert_eq!(parse("0x7f.1"), (ip("127.0.0.1"), 2, true, false));
+        assert_eq!(parse("0XFF.0xa.0.0"), (ip("255.10.0.0"), 4, true, false));
+        assert_eq!(parse("0x00000001"), (ip("0.0.0.1"), 1, true, false));
+        assert_eq!(parse("0177.0.0.1"), (ip("127.0.0.1"), 4, false, true));
+        assert_eq!(parse("010.0.0.1"), (ip("8.0.0.1"), 4, false, true));
+        assert_eq!(parse("00"), (ip("0.0.0.0"), 1, false, true));
+        assert_eq!(parse("0x7f.0.01"), (ip("127.0.0.1"), 3, true, true));
+    }
+
+    #[test]
+    fn test_parse_inet_aton_errors() {
+        assert_eq!(error(""), (0, Empty));
+        assert_eq!(error("."), (0, InvalidCharacter));
+        assert_eq!(error("1."), (2, UnexpectedEnd));
+        assert_eq!(error("1..2"), (2, InvalidCharacter));
+        assert_eq!(error("0x"), (2, UnexpectedEnd));
+        assert_eq!(error("0x.1"), (2, InvalidCharacter));
+        assert_eq!(error("0xg"), (2, InvalidCharacter));
+        assert_eq!(error("08"), (1, InvalidCharacter));
+        assert_eq!(error("1.2.3.4.5"), (7, TrailingCharacters));
+        assert_eq!(error("1.2.3.4 "), (7, InvalidCharacter));
+        assert_eq!(error("a"), (0, InvalidCharacter));
+        assert_eq!(error("4294967296"), (0, OctetOutOfRange));
+        assert_eq!(error("1.0x100000000"), (2, OctetOutOfRange));
+        assert_eq!(error("256.1"), (0, OctetOutOfRange));
+        assert_eq!(error("1.16777216"), (2, OctetOutOfRange));
+        assert_eq!(error("1.2.65536"), (4, OctetOutOfRange));
+        assert_eq!(error("1.2.3.256"), (6, OctetOutOfRange));
+        assert_eq!(error("1.256.3.4"), (2, OctetOutOfRange));
+    }
+}
@@ -1,7 +1,11 @@
 mod address;
+mod format;
 mod impls;
+mod inet_aton;
 mod parse;
 
 pub use self::address::*;
+pub use self::format::*;
 pub use self::impls::*;
+pub use self::inet_aton::*;
 pub use self::parse::*;
@@ -1,103 +1,241 @@
-use std::str::FromStr;
+use core::str::FromStr;
 
-use {Ipv4Address, ParsingFailed};
+use swar;
+use {Ipv4Address, ParseError, ParseOptions, ParsingFailed};
+use ParseErrorReason::*;
 
 impl FromStr for Ipv4Address {
     type Err = ParsingFailed;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(address) = swar::parse_ipv4(s.as_bytes()) {
+            return Ok(Ipv4Address(address));
+        }
         Self::parse(s.as_bytes())
-            .map_err(|()| ParsingFailed(s.into()))
+            .map_err(|e| ParsingFailed::new(s, e))
             .map(Ipv4Address)
     }
 }
 
 impl Ipv4Address {
-    pub(crate) fn parse(bytes: &[u8]) -> Result<u32, ()> {
-        if bytes.len() > 15 || bytes.len() < 7 {
-            return Err(());
+    /// Parse an IPv4 address, accepting only the syntaxes allowed by the given
+    /// [`ParseOptions`](struct.ParseOptions.html).
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, ParseErrorReason, ParseOptions};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let options = ParseOptions::strict().whitespace(true);
+    /// assert_eq!(
+    ///     Ipv4Address::parse_with(" 192.0.2.33\n", &options).unwrap(),
+    ///     Ipv4Address::from_str("192.0.2.33").unwrap()
+    /// );
+    ///
+    /// let err = Ipv4Address::parse_with("192.0.02.33", &options).unwrap_err();
+    /// assert_eq!(err.offset(), 6);
+    /// assert_eq!(err.reason(), ParseErrorReason::LeadingZero);
+    /// # }
+    /// ```
+    pub fn parse_with(s: &str, options: &ParseOptions) -> Result<Self, ParsingFailed> {
+        let (start, bytes) = options.trim(s.as_bytes());
+        Self::parse_with_options(bytes, options)
+            .map_err(|e| ParsingFailed::new(s, e.shift(start)))
+            .map(Ipv4Address)
+    }
+
+    /// Parse an IPv4 address from ASCII bytes. This is the same as `from_str()`, but the input
+    /// does not need to be valid UTF-8.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// assert_eq!(
+    ///     Ipv4Address::from_ascii(b"192.0.2.33").unwrap(),
+    ///     Ipv4Address::from_str("192.0.2.33").unwrap()
+    /// );
+    /// assert!(Ipv4Address::from_ascii(b"192.0.2.\xff").is_err());
+    /// # }
+    /// ```
+    pub fn from_ascii(bytes: &[u8]) -> Result<Self, ParsingFailed> {
+        if let Some(address) = swar::parse_ipv4(bytes) {
+            return Ok(Ipv4Address(address));
+        }
+        Self::parse(bytes)
+            .map_err(|e| ParsingFailed::from_bytes(bytes, e))
+            .map(Ipv4Address)
+    }
+
+    /// Parse an IPv4 address at the beginning of the given bytes, and return it along with the
+    /// number of bytes that were read. The bytes that follow the address are not checked, which
+    /// is useful to scan a buffer without splitting it first.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let (ip, len) = Ipv4Address::parse_prefix(b"192.0.2.33:8080").unwrap();
+    /// assert_eq!(ip, Ipv4Address::from_str("192.0.2.33").unwrap());
+    /// assert_eq!(len, 10);
+    ///
+    /// assert!(Ipv4Address::parse_prefix(b"192.0.2").is_err());
+    /// # }
+    /// ```
+    pub fn parse_prefix(bytes: &[u8]) -> Result<(Self, usize), ParsingFailed> {
+        Self::parse_prefix_with_options(bytes, &ParseOptions::new())
+            .map_err(|e| ParsingFailed::from_bytes(bytes, e))
+            .map(|(ip, len)| (Ipv4Address(ip), len))
+    }
+
+    /// Parse an IPv4 address at compile time. This is meant to be used in constants: an invalid
+    /// string is then a build error. The [`ipv4!`](macro.ipv4.html) macro is a shortcut for
+    /// this.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the string is not a valid IPv4 address.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// const VALUE: Ipv4Address = Ipv4Address::parse_const("192.0.2.33");
+    /// assert_eq!(VALUE, Ipv4Address::from_str("192.0.2.33").unwrap());
+    /// # }
+    /// ```
+    ///
+    /// ```rust,compile_fail
+    /// # use ipaddr::Ipv4Address;
+    /// const VALUE: Ipv4Address = Ipv4Address::parse_const("192.0.2.256");
+    /// ```
+    pub const fn parse_const(s: &str) -> Self {
+        match Ipv4Address::parse(s.as_bytes()) {
+            Ok(value) => Ipv4Address(value),
+            Err(e) => panic!("{}", e.reason().description()),
+        }
+    }
+
+    pub(crate) const fn parse(bytes: &[u8]) -> Result<u32, ParseError> {
+        Self::parse_with_options(bytes, &ParseOptions::new())
+    }
+
+    // The parsing functions are `const fn`, so that addresses can be parsed at compile time.
+    // This is why they use `while` loops and `match` instead of iterators and `?`.
+
+    pub(crate) const fn parse_with_options(
+        bytes: &[u8],
+        options: &ParseOptions,
+    ) -> Result<u32, ParseError> {
+        match Self::parse_prefix_with_options(bytes, options) {
+            // We finished reading the IP but there are still bytes to read
+            Ok((_, offset)) if offset < bytes.len() => {
+                Err(ParseError::new(offset, TrailingCharacters))
+            }
+            Ok((address, _)) => Ok(address),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub(crate) const fn parse_prefix_with_options(
+        bytes: &[u8],
+        options: &ParseOptions,
+    ) -> Result<(u32, usize), ParseError> {
+        if bytes.is_empty() {
+            return Err(ParseError::new(0, Empty));
         }
         let mut address: u32 = 0;
         let mut offset = 0;
-        for i in 0..4 {
+        let mut i = 0;
+        while i < 4 {
             if offset == bytes.len() {
-                return Err(());
-            }
-            let (bytes_read, octet) = read_octet(&bytes[offset..]);
-            if bytes_read == 0 {
-                // We should have been able to read an octet
-                return Err(());
+                return Err(ParseError::new(offset, UnexpectedEnd));
             }
+            // We should be able to read an octet
+            let (bytes_read, octet) = match read_octet(bytes, offset, options.leading_zeros) {
+                Ok(octet) => octet,
+                Err(reason) => return Err(ParseError::new(offset, reason)),
+            };
             offset += bytes_read;
-            address += u32::from(octet) << ((3 - i) * 8);
+            address += (octet as u32) << ((3 - i) * 8);
+
+            if offset < bytes.len() && is_decimal_digit(bytes[offset]) {
+                // read_octet() reads at most three digits
+                return Err(ParseError::new(offset, TooManyDigits));
+            }
 
             if i == 3 {
                 break;
             }
 
             // read the dot
-            if offset >= bytes.len() || bytes[offset] != b'.' {
-                return Err(());
+            if offset == bytes.len() {
+                return Err(ParseError::new(offset, UnexpectedEnd));
+            }
+            if bytes[offset] != b'.' {
+                return Err(ParseError::new(offset, InvalidCharacter));
             }
             offset += 1;
+            i += 1;
         }
-        if offset < bytes.len() {
-            // We finished reading the IP but there are still bytes to read
-            return Err(());
-        }
-        Ok(address)
+        Ok((address, offset))
     }
 }
 
-fn read_octet(bytes: &[u8]) -> (usize, u8) {
+/// Read up to three ASCII characters that represent decimal digits, starting at `offset`, and
+/// return their value, as well as the number of characters that were read. If `leading_zeros` is
+/// `false`, octets such as `01` are rejected.
+const fn read_octet(
+    bytes: &[u8],
+    offset: usize,
+    leading_zeros: bool,
+) -> Result<(usize, u8), ::ParseErrorReason> {
     let mut count = 0;
     let mut digits: [u8; 3] = [0; 3];
 
-    for b in bytes {
-        if is_decimal_digit(*b) {
-            digits[count] = decimal_to_digit(*b);
-            count += 1;
-            if count == 3 {
-                break;
-            }
-        } else {
+    while offset + count < bytes.len() && is_decimal_digit(bytes[offset + count]) {
+        digits[count] = decimal_to_digit(bytes[offset + count]);
+        count += 1;
+        if count == 3 {
             break;
         }
     }
 
     if count == 0 {
-        return (0, 0);
+        return Err(InvalidCharacter);
+    }
+
+    if !leading_zeros && count > 1 && digits[0] == 0 {
+        return Err(LeadingZero);
     }
 
     let mut res: u16 = 0;
-    for digit in &digits[0..count] {
-        res = 10 * res + u16::from(*digit);
+    let mut i = 0;
+    while i < count {
+        res = 10 * res + digits[i] as u16;
+        i += 1;
     }
 
     if res > 0xff {
-        return (0, 0);
+        return Err(OctetOutOfRange);
     }
 
-    (count, res as u8)
+    Ok((count, res as u8))
 }
 
 /// Check whether an ASCII character represents a decimal digit
-fn is_decimal_digit(byte: u8) -> bool {
-    match byte {
-        b'0'...b'9' => true,
-        _ => false,
-    }
+const fn is_decimal_digit(byte: u8) -> bool {
+    byte.is_ascii_digit()
 }
 
 /// Convert an ASCII character that represents a decimal into this digit
-fn decimal_to_digit(byte: u8) -> u8 {
+const fn decimal_to_digit(byte: u8) -> u8 {
     match byte {
-        b'0'...b'9' => byte - b'0',
+        b'0'..=b'9' => byte - b'0',
         _ => unreachable!(),
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
@@ -131,4 +269,47 @@ mod tests {
         assert!(Ipv4Address::from_str("256.0.0.1").is_err());
         assert!(Ipv4Address::from_str("25.0.0.256").is_err());
     }
+
+    fn error(s: &str) -> (usize, ::ParseErrorReason) {
+        let err = Ipv4Address::from_str(s).unwrap_err();
+        assert_eq!(err.input(), s);
+        (err.offset(), err.reason())
+    }
+
+    #[test]
+    fn test_error_details() {
+        assert_eq!(error(""), (0, Empty));
+        assert_eq!(error("1.2.3."), (6, UnexpectedEnd));
+        assert_eq!(error("1.2.3"), (5, UnexpectedEnd));
+        assert_eq!(error("1.2.3.4."), (7, TrailingCharacters));
+        assert_eq!(error("1.2.3.4 "), (7, TrailingCharacters));
+        assert_eq!(error(".1.2.3.4"), (0, InvalidCharacter));
+        assert_eq!(error("1.2..4"), (4, InvalidCharacter));
+        assert_eq!(error("1.2:3.4"), (3, InvalidCharacter));
+        assert_eq!(error("256.0.0.1"), (0, OctetOutOfRange));
+        assert_eq!(error("25.0.0.256"), (7, OctetOutOfRange));
+        assert_eq!(error("1.2.3.0004"), (9, TooManyDigits));
+        assert_eq!(error("1234.2.3.4"), (3, TooManyDigits));
+    }
+
+    #[test]
+    fn test_parse_prefix() {
+        let prefix = |s: &str| Ipv4Address::parse_prefix(s.as_bytes()).unwrap();
+        assert_eq!(prefix("1.2.3.4"), (Ipv4Address(0x0102_0304), 7));
+        assert_eq!(prefix("1.2.3.4."), (Ipv4Address(0x0102_0304), 7));
+        assert_eq!(prefix("1.2.3.4:80"), (Ipv4Address(0x0102_0304), 7));
+        assert_eq!(prefix("1.2.3.255.5"), (Ipv4Address(0x0102_03ff), 9));
+        assert!(Ipv4Address::parse_prefix(b"1.2.3").is_err());
+        assert!(Ipv4Address::parse_prefix(b"1.2.3.").is_err());
+        assert!(Ipv4Address::parse_prefix(b"1.2.3.1234").is_err());
+        assert!(Ipv4Address::parse_prefix(b"1.2.3.256").is_err());
+    }
+
+    #[test]
+    fn test_from_ascii() {
+        assert_eq!(Ipv4Address::from_ascii(b"1.2.3.4").unwrap(), Ipv4Address(0x0102_0304));
+        let err = Ipv4Address::from_ascii(b"1.2.\xff.4").unwrap_err();
+        assert_eq!(err.input(), "1.2.\u{fffd}.4");
+        assert_eq!((err.offset(), err.reason()), (4, InvalidCharacter));
+    }
 }
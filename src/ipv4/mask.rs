@@ -1,14 +1,17 @@
-use std::convert::TryFrom;
-use std::ops::{BitAnd, Not};
-use std::str::FromStr;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::ops::{BitAnd, Not};
+use core::str::FromStr;
 
-use core::fmt::{self, Binary, Formatter, LowerHex, UpperHex};
+use core::fmt::{self, Binary, Formatter, LowerHex, Octal, UpperHex};
 
-use {IPV4_MAX_PREFIXLEN, InvalidMask, Ipv4Address, ParsingFailed};
+use {IPV4_MAX_PREFIXLEN, InvalidMask, Ipv4Address, Ipv4Formatter, Ipv4StrBuf, ParseError,
+     ParseErrorReason, ParsingFailed};
 
 /// Check whether the given integer represents a valid IPv4 mask.
 // see https://codereview.stackexchange.com/a/197138/118470
-fn is_valid_mask(value: u32) -> bool {
+const fn is_valid_mask(value: u32) -> bool {
     value.count_zeros() == value.trailing_zeros()
 }
 
@@ -45,7 +48,7 @@ impl Ipv4Mask {
     /// assert_eq!(Ipv4Mask::from_prefixlen(13).unwrap(), Ipv4Mask::try_from(0xfff8_0000).unwrap());
     /// # }
     /// ```
-    pub fn from_prefixlen(prefixlen: u8) -> Result<Self, InvalidMask> {
+    pub const fn from_prefixlen(prefixlen: u8) -> Result<Self, InvalidMask> {
         match prefixlen {
             IPV4_MAX_PREFIXLEN => Ok(Ipv4Mask(u32::max_value())),
             0 => Ok(Ipv4Mask(0)),
@@ -58,6 +61,12 @@ impl Ipv4Mask {
         }
     }
 
+    /// Create a formatter to stringify this IPv4 mask.
+    /// See [`Ipv4Formatter<'a, W>`](struct.Ipv4Formatter.html) for more details.
+    pub fn formatter<'a, W: fmt::Write>(self, writer: &'a mut W) -> Ipv4Formatter<'a, W> {
+        Ipv4Address::from(self).formatter(writer)
+    }
+
     /// Return a human readable representation of the IPv4 mask.
     ///
     /// ```rust
@@ -70,22 +79,27 @@ impl Ipv4Mask {
     /// assert_eq!(mask.to_string(), "255.248.0.0".to_string());
     /// # }
     /// ```
+    #[cfg(feature = "alloc")]
     pub fn to_string(self) -> String {
         Ipv4Address::from(self).to_string()
     }
+
+    /// Return a human readable representation of the IPv4 mask, in a buffer allocated on the
+    /// stack.
+    pub fn to_str_buf(self) -> Ipv4StrBuf {
+        Ipv4Address::from(self).to_str_buf()
+    }
 }
 
 impl fmt::Display for Ipv4Mask {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str(&self.to_string())
+        fmt::Display::fmt(&Ipv4Address::from(*self), f)
     }
 }
 
 impl fmt::Debug for Ipv4Mask {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str("Ipv4Mask(")?;
-        f.write_str(&self.to_string())?;
-        f.write_str(")")
+        write!(f, "Ipv4Mask({})", self)
     }
 }
 
@@ -177,6 +191,12 @@ impl Binary for Ipv4Mask {
     }
 }
 
+impl Octal for Ipv4Mask {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        <u32 as Octal>::fmt(&self.0, f)
+    }
+}
+
 impl BitAnd<Ipv4Mask> for Ipv4Address {
     type Output = Ipv4Address;
     fn bitand(self, rhs: Ipv4Mask) -> Self::Output {
@@ -209,8 +229,63 @@ impl FromStr for Ipv4Mask {
     type Err = ParsingFailed;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let ip = Ipv4Address::from_str(s)?;
-        Ipv4Mask::try_from(ip).map_err(|_| ParsingFailed(s.into()))
+        Ipv4Mask::parse(s.as_bytes()).map_err(|e| ParsingFailed::new(s, e))
+    }
+}
+
+impl Ipv4Mask {
+    /// Parse a mask from ASCII bytes. This is the same as `from_str()`, but the input does not
+    /// need to be valid UTF-8.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Mask;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// assert_eq!(
+    ///     Ipv4Mask::from_ascii(b"255.255.255.0").unwrap(),
+    ///     Ipv4Mask::from_str("255.255.255.0").unwrap()
+    /// );
+    /// assert!(Ipv4Mask::from_ascii(b"255.0.255.0").is_err());
+    /// # }
+    /// ```
+    pub fn from_ascii(bytes: &[u8]) -> Result<Self, ParsingFailed> {
+        Ipv4Mask::parse(bytes).map_err(|e| ParsingFailed::from_bytes(bytes, e))
+    }
+
+    /// Parse an IPv4 mask at compile time. This is meant to be used in constants: an invalid
+    /// string is then a build error. The [`ipv4_mask!`](macro.ipv4_mask.html) macro is a shortcut for
+    /// this.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the string is not a valid IPv4 mask.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Mask;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// const VALUE: Ipv4Mask = Ipv4Mask::parse_const("255.255.255.0");
+    /// assert_eq!(VALUE, Ipv4Mask::from_prefixlen(24).unwrap());
+    /// # }
+    /// ```
+    ///
+    /// ```rust,compile_fail
+    /// # use ipaddr::Ipv4Mask;
+    /// const VALUE: Ipv4Mask = Ipv4Mask::parse_const("255.0.255.0");
+    /// ```
+    pub const fn parse_const(s: &str) -> Self {
+        match Ipv4Mask::parse(s.as_bytes()) {
+            Ok(value) => value,
+            Err(e) => panic!("{}", e.reason().description()),
+        }
+    }
+
+    pub(crate) const fn parse(bytes: &[u8]) -> Result<Self, ParseError> {
+        match Ipv4Address::parse(bytes) {
+            Ok(value) if is_valid_mask(value) => Ok(Ipv4Mask(value)),
+            Ok(_) => Err(ParseError::new(0, ParseErrorReason::InvalidMask)),
+            Err(e) => Err(e),
+        }
     }
 }
 
@@ -1,5 +1,7 @@
 mod address;
 pub use self::address::*;
+mod network;
+pub use self::network::*;
 mod mask;
 pub use self::mask::*;
 mod constants;
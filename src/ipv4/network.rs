@@ -0,0 +1,250 @@
+use core::fmt;
+use core::str::FromStr;
+
+use {Ipv4Address, Ipv4Mask, ParseError, ParsingFailed};
+use ParseErrorReason::*;
+
+/// An IPv4 network, i.e. an IPv4 address and a mask.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Ipv4Network(pub(crate) Ipv4Address, pub(crate) Ipv4Mask);
+
+impl Ipv4Network {
+    /// Create a new network address from the given address and mask.
+    pub fn new(ip: Ipv4Address, mask: Ipv4Mask) -> Self {
+        Ipv4Network(ip, mask)
+    }
+
+    pub fn host(&self) -> Ipv4Address {
+        self.0 & self.host_mask()
+    }
+
+    pub fn network(&self) -> Ipv4Address {
+        self.0 & self.1
+    }
+
+    pub fn host_mask(&self) -> Ipv4Address {
+        !self.1
+    }
+
+    pub fn broadcast(&self) -> Ipv4Address {
+        self.0 | self.host_mask()
+    }
+
+    pub fn mask(&self) -> Ipv4Mask {
+        self.1
+    }
+
+    pub fn ip(&self) -> &Ipv4Address {
+        &self.0
+    }
+
+    pub fn ip_mut(&mut self) -> &mut Ipv4Address {
+        &mut self.0
+    }
+
+    pub fn mask_mut(&mut self) -> &mut Ipv4Mask {
+        &mut self.1
+    }
+}
+
+impl fmt::Display for Ipv4Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.0, self.1.prefixlen())
+    }
+}
+
+impl fmt::Debug for Ipv4Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Ipv4Network({}/{})", self.0, self.1.prefixlen())
+    }
+}
+
+impl FromStr for Ipv4Network {
+    type Err = ParsingFailed;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ipv4Network::parse(s.as_bytes()).map_err(|e| ParsingFailed::new(s, e))
+    }
+}
+
+impl Ipv4Network {
+    /// Parse a network from ASCII bytes. This is the same as `from_str()`, but the input does not
+    /// need to be valid UTF-8.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Network;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// assert_eq!(
+    ///     Ipv4Network::from_ascii(b"192.0.2.0/24").unwrap(),
+    ///     Ipv4Network::from_str("192.0.2.0/24").unwrap()
+    /// );
+    /// assert!(Ipv4Network::from_ascii(b"192.0.2.0/33").is_err());
+    /// # }
+    /// ```
+    pub fn from_ascii(bytes: &[u8]) -> Result<Self, ParsingFailed> {
+        Ipv4Network::parse(bytes).map_err(|e| ParsingFailed::from_bytes(bytes, e))
+    }
+
+    /// Parse an IPv4 network at compile time. This is meant to be used in constants: an invalid
+    /// string is then a build error. The [`ipv4_network!`](macro.ipv4_network.html) macro is a shortcut for
+    /// this.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the string is not a valid IPv4 network.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Network;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// const VALUE: Ipv4Network = Ipv4Network::parse_const("192.0.2.0/24");
+    /// assert_eq!(VALUE, Ipv4Network::from_str("192.0.2.0/24").unwrap());
+    /// # }
+    /// ```
+    ///
+    /// ```rust,compile_fail
+    /// # use ipaddr::Ipv4Network;
+    /// const VALUE: Ipv4Network = Ipv4Network::parse_const("192.0.2.0/33");
+    /// ```
+    pub const fn parse_const(s: &str) -> Self {
+        match Ipv4Network::parse(s.as_bytes()) {
+            Ok(value) => value,
+            Err(e) => panic!("{}", e.reason().description()),
+        }
+    }
+
+    // This is a `const fn`, so that networks can be parsed at compile time. This is why it uses
+    // `while` loops and `match` instead of iterators and `?`.
+    const fn parse(bytes: &[u8]) -> Result<Self, ParseError> {
+        let mut slash = 0;
+        while slash < bytes.len() && bytes[slash] != b'/' {
+            slash += 1;
+        }
+        if slash == bytes.len() {
+            return Err(ParseError::new(bytes.len(), UnexpectedEnd));
+        }
+        let (ip_bytes, mask_bytes) = bytes.split_at(slash);
+        let ip = match Ipv4Address::parse(ip_bytes) {
+            Ok(ip) => Ipv4Address(ip),
+            Err(e) => return Err(e),
+        };
+
+        let mask_bytes = mask_bytes.split_at(1).1;
+        if mask_bytes.is_empty() {
+            return Err(ParseError::new(bytes.len(), UnexpectedEnd));
+        }
+
+        // The most common is to represent the mask with as a prefix length, so we try to parse the
+        // string as an integer first.
+        if let Some(prefix_len) = parse_prefixlen(mask_bytes) {
+            if prefix_len <= 0xff {
+                if let Ok(mask) = Ipv4Mask::from_prefixlen(prefix_len as u8) {
+                    return Ok(Ipv4Network(ip, mask));
+                }
+            }
+            return Err(ParseError::new(slash + 1, InvalidPrefixLength));
+        }
+
+        // If that didn't work, may it has been specified as an IPv4
+        match Ipv4Mask::parse(mask_bytes) {
+            Ok(mask) => Ok(Ipv4Network(ip, mask)),
+            Err(e) => Err(e.shift(slash + 1)),
+        }
+    }
+}
+
+/// Parse a prefix length. Return `None` if the bytes are not all decimal digits. Values that do
+/// not fit in a `u16` are replaced by `u16::MAX`.
+const fn parse_prefixlen(bytes: &[u8]) -> Option<u16> {
+    let mut value: u16 = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            return None;
+        }
+        value = value.saturating_mul(10).saturating_add((bytes[i] - b'0') as u16);
+        i += 1;
+    }
+    Some(value)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use core::convert::TryFrom;
+
+    const IP: u32 = 0xc000_02a1;
+
+    #[test]
+    fn test_from_str() {
+        let s = "192.0.2.161/24";
+        let expected = Ipv4Network::new(IP.into(), Ipv4Mask::from_prefixlen(24).unwrap());
+        assert_eq!(Ipv4Network::from_str(s).unwrap(), expected);
+
+        let s = "192.0.2.161/255.255.255.0";
+        assert_eq!(Ipv4Network::from_str(s).unwrap(), expected);
+
+        let s = "192.0.2.161/0";
+        let expected = Ipv4Network::new(IP.into(), Ipv4Mask::try_from(0).unwrap());
+        assert_eq!(Ipv4Network::from_str(s).unwrap(), expected);
+
+        let s = "192.0.2.161/32";
+        let expected = Ipv4Network::new(IP.into(), Ipv4Mask::try_from(u32::max_value()).unwrap());
+        assert_eq!(Ipv4Network::from_str(s).unwrap(), expected);
+
+        assert!(Ipv4Network::from_str("192.0.2.161").is_err());
+        assert!(Ipv4Network::from_str("192.0.2.161/33").is_err());
+        assert!(Ipv4Network::from_str("192.0.2.161/255.0.255.0").is_err());
+    }
+
+    fn get_net(prefix: u8) -> Ipv4Network {
+        Ipv4Network::new(IP.into(), Ipv4Mask::from_prefixlen(prefix).unwrap())
+    }
+
+    #[test]
+    fn test_network() {
+        assert_eq!(get_net(8).network(), Ipv4Address(0xc000_0000));
+        assert_eq!(get_net(24).network(), Ipv4Address(0xc000_0200));
+        assert_eq!(get_net(27).network(), Ipv4Address(0xc000_02a0));
+        assert_eq!(get_net(32).network(), Ipv4Address(IP));
+    }
+
+    #[test]
+    fn test_host() {
+        assert_eq!(get_net(8).host(), Ipv4Address(0x0000_02a1));
+        assert_eq!(get_net(24).host(), Ipv4Address(0x0000_00a1));
+        assert_eq!(get_net(27).host(), Ipv4Address(0x0000_0001));
+        assert_eq!(get_net(32).host(), Ipv4Address(0));
+    }
+
+    #[test]
+    fn test_broadcast() {
+        assert_eq!(get_net(8).broadcast(), Ipv4Address(0xc0ff_ffff));
+        assert_eq!(get_net(24).broadcast(), Ipv4Address(0xc000_02ff));
+        assert_eq!(get_net(27).broadcast(), Ipv4Address(0xc000_02bf));
+        assert_eq!(get_net(32).broadcast(), Ipv4Address(IP));
+    }
+
+    #[test]
+    fn test_to_string() {
+        assert_eq!(get_net(27).to_string(), "192.0.2.161/27");
+        assert_eq!(format!("{}", get_net(0)), "192.0.2.161/0");
+        assert_eq!(format!("{:?}", get_net(32)), "Ipv4Network(192.0.2.161/32)");
+    }
+
+    #[test]
+    fn test_error_details() {
+        let error = |s| {
+            let err = Ipv4Network::from_str(s).unwrap_err();
+            (err.offset(), err.reason())
+        };
+        assert_eq!(error("192.0.2.0"), (9, UnexpectedEnd));
+        assert_eq!(error("192.0.2.0/"), (10, UnexpectedEnd));
+        assert_eq!(error("192.0.2/24"), (7, UnexpectedEnd));
+        assert_eq!(error("192.0.2.0/33"), (10, InvalidPrefixLength));
+        assert_eq!(error("192.0.2.0/255.0.255.0"), (10, InvalidMask));
+        assert_eq!(error("192.0.2.0/255.255.256.0"), (18, OctetOutOfRange));
+        assert_eq!(error("192.0.2.0/24/8"), (12, InvalidCharacter));
+    }
+}
@@ -1,7 +1,10 @@
-use std::convert::TryFrom;
-use std::fmt;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::fmt;
 
-use {IPV6_LOOPBACK, IPV6_UNSPECIFIED, Ipv6Formatter, MalformedAddress};
+use {IPV6_LOOPBACK, IPV6_UNSPECIFIED, IpAddress, Ipv4Address, Ipv6Formatter, Ipv6StrBuf,
+     MalformedAddress};
 
 #[derive(Copy, Eq, PartialEq, Hash, Clone)]
 pub enum Ipv6AddressScope {
@@ -31,7 +34,38 @@ impl From<Ipv6AddressScope> for u8 {
     }
 }
 
-// An Ipv6 address
+/// An Ipv6 address
+///
+/// # Compatibility with `std::net::Ipv6Addr`
+///
+/// `from_str()` accepts the same strings as `Ipv6Addr`, except that the octets of an embedded
+/// IPv4 address may have leading zeros, like `::ffff:192.0.2.033`. Use
+/// [`ParseOptions::strict()`](struct.ParseOptions.html#method.strict) to reject them.
+///
+/// Formatting gives the same strings as `Ipv6Addr`, except for IPv4-mapped addresses: `Ipv6Addr`
+/// writes `::ffff:192.0.2.33`, while the default here is `::ffff:c000:221`. Use
+/// [`Ipv4Suffix::Auto`](enum.Ipv4Suffix.html) to write the IPv4 address.
+///
+/// ```rust
+/// # use ipaddr::{Ipv4Suffix, Ipv6Address, ParseOptions};
+/// # use std::net::Ipv6Addr;
+/// # use std::str::FromStr;
+/// # fn main() {
+/// let s = "::ffff:192.0.2.033";
+/// assert!(Ipv6Address::from_str(s).is_ok());
+/// assert!(Ipv6Address::parse_with(s, &ParseOptions::strict()).is_err());
+/// assert!(Ipv6Addr::from_str(s).is_err());
+///
+/// let ip = Ipv6Address::from_str("::ffff:192.0.2.33").unwrap();
+/// let std_ip = Ipv6Addr::from(u128::from(ip));
+/// assert_eq!(ip.to_string(), "::ffff:c000:221");
+/// assert_eq!(std_ip.to_string(), "::ffff:192.0.2.33");
+///
+/// let mut s = String::new();
+/// ip.formatter(&mut s).ipv4_suffix(Ipv4Suffix::Auto).write().unwrap();
+/// assert_eq!(s, std_ip.to_string());
+/// # }
+/// ```
 #[derive(Copy, Eq, PartialEq, Hash, Clone)]
 pub struct Ipv6Address(pub(crate) u128);
 
@@ -185,6 +219,80 @@ impl Ipv6Address {
         *self & 0xffff_ffff_ffff_ffff_ffff_ffff_0000_0000 == 0xffff_0000_0000.into()
     }
 
+    /// Return the IPv4 address embedded in this address if it is an IPv4-mapped address
+    /// (`::ffff:a.b.c.d`) or an IPv4-compatible address (`::a.b.c.d`), and `None` otherwise.
+    ///
+    /// Note that `::` and `::1` are IPv4-compatible addresses, so they are converted into
+    /// `0.0.0.0` and `0.0.0.1`. Use [`to_ipv4_mapped()`](#method.to_ipv4_mapped) to only convert
+    /// IPv4-mapped addresses.
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv6Address};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ipv4 = Ipv4Address::from_str("192.0.2.33").unwrap();
+    /// assert_eq!(Ipv6Address::from_str("::ffff:192.0.2.33").unwrap().to_ipv4(), Some(ipv4));
+    /// assert_eq!(Ipv6Address::from_str("::192.0.2.33").unwrap().to_ipv4(), Some(ipv4));
+    /// assert_eq!(Ipv6Address::from_str("::1").unwrap().to_ipv4(), Some(Ipv4Address::from(1)));
+    /// assert_eq!(Ipv6Address::from_str("fe80::c000:221").unwrap().to_ipv4(), None);
+    /// # }
+    /// ```
+    pub fn to_ipv4(&self) -> Option<Ipv4Address> {
+        if self.is_ipv4_mapped() || self.is_ipv4_compatible() {
+            Some(Ipv4Address(self.0 as u32))
+        } else {
+            None
+        }
+    }
+
+    /// Return the IPv4 address embedded in this address if it is an IPv4-mapped address
+    /// (`::ffff:a.b.c.d`), and `None` otherwise.
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv6Address};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ipv4 = Ipv4Address::from_str("192.0.2.33").unwrap();
+    /// assert_eq!(Ipv6Address::from_str("::ffff:192.0.2.33").unwrap().to_ipv4_mapped(), Some(ipv4));
+    /// assert_eq!(Ipv6Address::from_str("::192.0.2.33").unwrap().to_ipv4_mapped(), None);
+    /// # }
+    /// ```
+    pub fn to_ipv4_mapped(&self) -> Option<Ipv4Address> {
+        if self.is_ipv4_mapped() {
+            Some(Ipv4Address(self.0 as u32))
+        } else {
+            None
+        }
+    }
+
+    /// Convert this address into an IPv4 address if it is an IPv4-mapped address
+    /// (`::ffff:a.b.c.d`). Other addresses, including IPv4-compatible addresses, are returned
+    /// unchanged.
+    ///
+    /// This is useful to normalize the addresses returned by dual-stack sockets, which represent
+    /// IPv4 peers as IPv4-mapped addresses.
+    ///
+    /// ```rust
+    /// # use ipaddr::{IpAddress, Ipv4Address, Ipv6Address};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = Ipv6Address::from_str("::ffff:192.0.2.33").unwrap();
+    /// assert_eq!(
+    ///     ip.to_canonical(),
+    ///     IpAddress::V4(Ipv4Address::from_str("192.0.2.33").unwrap())
+    /// );
+    ///
+    /// let ip = Ipv6Address::from_str("::192.0.2.33").unwrap();
+    /// assert_eq!(ip.to_canonical(), IpAddress::V6(ip));
+    /// # }
+    /// ```
+    pub fn to_canonical(&self) -> IpAddress {
+        match self.to_ipv4_mapped() {
+            Some(ipv4) => IpAddress::V4(ipv4),
+            None => IpAddress::V6(*self),
+        }
+    }
+
     /// Return the address as an array of bytes
     pub fn octets(&self) -> [u8; 16] {
         let mut bytes: [u8; 16] = [0; 16];
@@ -251,6 +359,7 @@ impl Ipv6Address {
     /// // the same representation is used in the Display implementation
     /// assert_eq!(format!("{}", ip), "fe80::8657:e6fe:8d5:5325");
     /// # }
+    #[cfg(feature = "alloc")]
     pub fn to_string(&self) -> String {
         let mut s = String::with_capacity(40);
         // https://doc.rust-lang.org/std/fmt/index.html#formatting-traits
@@ -262,11 +371,43 @@ impl Ipv6Address {
             .expect("string formatting failed?!");
         s
     }
+
+    /// Return the same representation as [`to_string()`](#method.to_string), in a buffer
+    /// allocated on the stack.
+    pub fn to_str_buf(&self) -> Ipv6StrBuf {
+        let mut buf = Ipv6StrBuf::new();
+        // The longest IPv6 address is 45 bytes long, so this cannot fail
+        self.formatter(&mut buf)
+            .rfc_5952()
+            .write()
+            .expect("Ipv6StrBuf is too small?!");
+        buf
+    }
 }
 
+/// The alternate flag (`{:#}`) selects the
+/// [`expanded()`](struct.Ipv6Formatter.html#method.expanded) representation. Width, fill and
+/// alignment are honored.
+///
+/// ```rust
+/// # use ipaddr::Ipv6Address;
+/// # fn main() {
+/// let ip = Ipv6Address::from(0xfe80_0000_0000_0000_8657_e6fe_08d5_5325);
+/// assert_eq!(format!("{}", ip), "fe80::8657:e6fe:8d5:5325");
+/// assert_eq!(format!("{:#}", ip), "fe80:0000:0000:0000:8657:e6fe:08d5:5325");
+/// assert_eq!(format!("[{:>26}]", ip), "[  fe80::8657:e6fe:8d5:5325]");
+/// # }
+/// ```
 impl fmt::Display for Ipv6Address {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.formatter(f).write()
+        let expanded = f.alternate();
+        if f.width().is_none() && f.precision().is_none() {
+            return self.formatter(f).expanded_if(expanded).write();
+        }
+        // Padding requires knowing the length first
+        let mut buf = Ipv6StrBuf::new();
+        self.formatter(&mut buf).expanded_if(expanded).write()?;
+        f.pad(&buf)
     }
 }
 
@@ -291,7 +432,17 @@ impl From<[u8; 16]> for Ipv6Address {
     }
 }
 
-#[cfg(test)]
+impl From<[u16; 8]> for Ipv6Address {
+    fn from(hextets: [u16; 8]) -> Self {
+        Ipv6Address(
+            hextets
+                .iter()
+                .fold(0, |address, hextet| (address << 16) | u128::from(*hextet)),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
     #[test]
@@ -316,7 +467,7 @@ mod tests {
     }
     #[test]
     fn test_link_local() {
-        use std::str::FromStr;
+        use core::str::FromStr;
         assert!(
             Ipv6Address::from_str("fe80::")
                 .unwrap()
@@ -344,4 +495,55 @@ mod tests {
                 .is_link_local_unicast()
         );
     }
+
+    #[test]
+    fn test_ipv4_conversions() {
+        let ipv4 = Ipv4Address(0xc000_0221);
+
+        let mapped = ipv4.to_ipv6_mapped();
+        assert!(mapped.is_ipv4_mapped());
+        assert_eq!(mapped.to_ipv4(), Some(ipv4));
+        assert_eq!(mapped.to_ipv4_mapped(), Some(ipv4));
+        assert_eq!(mapped.to_canonical(), IpAddress::V4(ipv4));
+
+        let compatible = ipv4.to_ipv6_compatible();
+        assert!(compatible.is_ipv4_compatible());
+        assert_eq!(compatible.to_ipv4(), Some(ipv4));
+        assert_eq!(compatible.to_ipv4_mapped(), None);
+        assert_eq!(compatible.to_canonical(), IpAddress::V6(compatible));
+
+        let ip = Ipv6Address(0x0001_0000_0000_0000_0000_ffff_c000_0221);
+        assert_eq!(ip.to_ipv4(), None);
+        assert_eq!(ip.to_ipv4_mapped(), None);
+    }
+
+    #[test]
+    fn test_to_str_buf() {
+        for ip in &[
+            Ipv6Address(0),
+            Ipv6Address(0xfe80_0000_0000_0000_8657_e6fe_08d5_5325),
+            Ipv6Address(0x0000_0000_0000_0000_0000_ffff_c000_0221),
+            Ipv6Address(0x0001_0000_0000_0000_0000_ffff_ffff_ffff),
+            Ipv6Address(u128::max_value()),
+        ] {
+            assert_eq!(ip.to_str_buf().as_str(), ip.to_string());
+            assert_eq!(format!("{}", ip), ip.to_string());
+        }
+    }
+
+    #[test]
+    fn test_display_flags() {
+        let ip = Ipv6Address(0xfe80_0000_0000_0000_0000_0000_0000_0001);
+        assert_eq!(format!("[{:>10}]", ip), "[   fe80::1]");
+        assert_eq!(format!("[{:-<10}]", ip), "[fe80::1---]");
+        assert_eq!(format!("{:#}", ip), "fe80:0000:0000:0000:0000:0000:0000:0001");
+        assert_eq!(
+            format!("[{:>#41}]", ip),
+            "[  fe80:0000:0000:0000:0000:0000:0000:0001]"
+        );
+        assert_eq!(format!("{:o}", Ipv6Address(0o17)), "17");
+        let mask = ::Ipv6Mask::from_prefixlen(16).unwrap();
+        assert_eq!(format!("[{:^10}]", mask), "[  ffff::  ]");
+        assert_eq!(format!("{:#}", mask), "ffff:0000:0000:0000:0000:0000:0000:0000");
+    }
 }
@@ -1,4 +1,27 @@
-use std::fmt::{Result, Write};
+use core::fmt::{Error, Result, Write};
+
+use Ipv6Address;
+#[cfg(feature = "alloc")]
+use Ipv6Zone;
+
+/// Whether an [`Ipv6Formatter`](struct.Ipv6Formatter.html) should write the 32 least significant
+/// bits of an address as an IPv4 address in dotted-quad notation (`::ffff:1.2.3.4`) instead of two
+/// hextets (`::ffff:102:304`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Ipv4Suffix {
+    /// Always write the hextets (this is the default)
+    Never,
+    /// Always write the last 32 bits as an IPv4 address
+    Always,
+    /// Write the last 32 bits as an IPv4 address for the addresses that are known to embed an
+    /// IPv4 address, as recommended by [RFC5952](https://tools.ietf.org/html/rfc5952#section-5):
+    ///
+    /// - IPv4-mapped addresses (`::ffff:a.b.c.d`)
+    /// - IPv4-compatible addresses (`::a.b.c.d`), except `::` and the addresses in `::/112` such
+    ///   as the loopback `::1`
+    /// - addresses in the NAT64 well-known prefix (`64:ff9b::a.b.c.d`)
+    Auto,
+}
 
 /// Configurable IPv6 formatter. By default, this formatter will format IPv6 address as described in [RFC5952](https://tools.ietf.org/html/rfc5952):
 ///
@@ -18,7 +41,7 @@ use std::fmt::{Result, Write};
 /// # Examples
 ///
 /// ```rust
-/// # use ipaddr::Ipv6Address;
+/// # use ipaddr::{Ipv4Suffix, Ipv6Address};
 /// # fn main() {
 /// let ip = Ipv6Address::from(0xfe80_0000_0000_0000_8657_0000_08d5_5325);
 /// let mut s = String::with_capacity(40);
@@ -73,12 +96,22 @@ use std::fmt::{Result, Write};
 ///     .write()
 ///     .unwrap();
 /// assert_eq!(s, "FE80:0000:0000:0000:8657:0000:08D5:5325");
+///
+/// // Write the last 32 bits as an IPv4 address
+/// s.truncate(0);
+/// ip.formatter(&mut s).ipv4_suffix(Ipv4Suffix::Always).write().unwrap();
+/// assert_eq!(s, "fe80::8657:0:8.213.83.37");
 /// # }
 /// ```
 pub struct Ipv6Formatter<'writer, W: 'writer> {
     ellipsis: bool,
     leading_zeros: bool,
     upper_case: bool,
+    ipv4_suffix: Ipv4Suffix,
+    #[cfg(feature = "alloc")]
+    zone: Option<&'writer Ipv6Zone>,
+    #[cfg(feature = "alloc")]
+    url_encoded_zone: bool,
     writer: &'writer mut W,
     hextets: [u16; 8],
 }
@@ -92,11 +125,22 @@ where
             ellipsis: true,
             leading_zeros: false,
             upper_case: false,
+            ipv4_suffix: Ipv4Suffix::Never,
+            #[cfg(feature = "alloc")]
+            zone: None,
+            #[cfg(feature = "alloc")]
+            url_encoded_zone: false,
             writer,
             hextets,
         }
     }
 
+    #[cfg(feature = "alloc")]
+    pub(crate) fn with_zone(mut self, zone: &'writer Ipv6Zone) -> Self {
+        self.zone = Some(zone);
+        self
+    }
+
     /// Use the formatting described in
     /// [RFC5952](https://tools.ietf.org/html/rfc5952) (this is the default)
     ///
@@ -147,6 +191,15 @@ where
         self.ellipsis(false).leading_zeros(true)
     }
 
+    /// Use the [`expanded()`](#method.expanded) representation if `flag` is `true`.
+    pub(crate) fn expanded_if(self, flag: bool) -> Self {
+        if flag {
+            self.expanded()
+        } else {
+            self
+        }
+    }
+
     /// Set whether the longest sequence of zeros should be elided or not. By default, this is
     /// `true`.
     ///
@@ -221,38 +274,103 @@ where
         self
     }
 
-    fn longest_zero_sequence(&self) -> Option<(usize, usize)> {
+    /// Set whether the 32 least significant bits of the address should be written as an IPv4
+    /// address. By default, this is [`Ipv4Suffix::Never`](enum.Ipv4Suffix.html).
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Suffix, Ipv6Address, Ipv6Formatter};
+    /// # fn main() {
+    /// let ip = Ipv6Address::from(0xffff_c000_0221);
+    /// let mut s = String::with_capacity(40);
+    ///
+    /// ip.formatter(&mut s).write().unwrap();
+    /// assert_eq!(s, "::ffff:c000:221");
+    ///
+    /// s.truncate(0);
+    /// ip.formatter(&mut s).ipv4_suffix(Ipv4Suffix::Auto).write().unwrap();
+    /// assert_eq!(s, "::ffff:192.0.2.33");
+    ///
+    /// // In automatic mode, addresses that don't embed an IPv4 address are left untouched
+    /// let ip = Ipv6Address::from(0xfe80_0000_0000_0000_0000_0000_c000_0221);
+    /// s.truncate(0);
+    /// ip.formatter(&mut s).ipv4_suffix(Ipv4Suffix::Auto).write().unwrap();
+    /// assert_eq!(s, "fe80::c000:221");
+    ///
+    /// s.truncate(0);
+    /// ip.formatter(&mut s).ipv4_suffix(Ipv4Suffix::Always).write().unwrap();
+    /// assert_eq!(s, "fe80::192.0.2.33");
+    /// # }
+    /// ```
+    pub fn ipv4_suffix(mut self, mode: Ipv4Suffix) -> Self {
+        self.ipv4_suffix = mode;
+        self
+    }
+
+    /// Set whether the zone identifier of a scoped address should be written as described in
+    /// [RFC6874](https://tools.ietf.org/html/rfc6874), for use in URIs: the `%` delimiter is
+    /// written `%25`, and the characters of the zone that are not allowed in URIs are
+    /// percent-encoded. By default, this is `false`. This has no effect for addresses that don't
+    /// have a zone identifier.
+    ///
+    /// ```rust
+    /// # use ipaddr::ScopedIpv6Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = ScopedIpv6Address::from_str("fe80::1%eth0").unwrap();
+    /// let mut s = String::with_capacity(40);
+    ///
+    /// ip.formatter(&mut s).write().unwrap();
+    /// assert_eq!(s, "fe80::1%eth0");
+    ///
+    /// s.truncate(0);
+    /// ip.formatter(&mut s).url_encoded_zone(true).write().unwrap();
+    /// assert_eq!(s, "fe80::1%25eth0");
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn url_encoded_zone(mut self, flag: bool) -> Self {
+        self.url_encoded_zone = flag;
+        self
+    }
+
+    /// Return `true` if the 32 least significant bits should be written as an IPv4 address.
+    fn has_ipv4_suffix(&self) -> bool {
+        match self.ipv4_suffix {
+            Ipv4Suffix::Never => false,
+            Ipv4Suffix::Always => true,
+            Ipv4Suffix::Auto => {
+                let ip = Ipv6Address::from(self.hextets);
+                ip.is_ipv4_mapped()
+                    || (ip.is_ipv4_compatible() && self.hextets[6] != 0)
+                    || ip.is_nat64_well_known()
+            }
+        }
+    }
+
+    /// Return the first and last index of the longest sequence of zeros among the `len` first
+    /// hextets. As required by [RFC5952](https://tools.ietf.org/html/rfc5952#section-4.2), a
+    /// single zero hextet is not a sequence, and the first sequence wins when several sequences
+    /// have the same length.
+    fn longest_zero_sequence(&self, len: usize) -> Option<(usize, usize)> {
         let mut start: Option<usize> = None;
-        let mut end: Option<usize> = None;
         let mut longest_seq: Option<(usize, usize)> = None;
 
-        for (i, h) in self.hextets.iter().enumerate() {
-            if *h == 0 {
+        for i in 0..=len {
+            if i < len && self.hextets[i] == 0 {
                 if start.is_none() {
                     start = Some(i);
-                } else {
-                    end = Some(i);
                 }
-            } else if end.is_some() {
-                let cur_start = start.unwrap();
-                let cur_end = end.unwrap();
-
-                if let Some((prev_start, prev_end)) = longest_seq {
-                    if prev_end - prev_start < cur_end - cur_start {
-                        longest_seq = Some((cur_start, cur_end));
-                        start = None;
-                        end = None;
-                    }
-                } else {
+            } else if let Some(cur_start) = start.take() {
+                let cur_end = i - 1;
+                let is_longest = match longest_seq {
+                    Some((prev_start, prev_end)) => prev_end - prev_start < cur_end - cur_start,
+                    None => cur_start < cur_end,
+                };
+                if is_longest {
                     longest_seq = Some((cur_start, cur_end));
-                    start = None;
-                    end = None;
                 }
             }
         }
-        if end.is_some() && longest_seq.is_none() {
-            longest_seq = Some((start.unwrap(), end.unwrap()));
-        }
         longest_seq
     }
 
@@ -271,14 +389,60 @@ where
     /// ip.formatter(&mut s).write().unwrap();
     /// # }
     pub fn write(&mut self) -> Result {
+        self.write_address()?;
+        self.write_zone()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn write_zone(&mut self) -> Result {
+        match self.zone {
+            Some(zone) if self.url_encoded_zone => {
+                self.writer.write_str("%25")?;
+                zone.write_url_encoded(self.writer)
+            }
+            Some(zone) => write!(self.writer, "%{}", zone),
+            None => Ok(()),
+        }
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn write_zone(&mut self) -> Result {
+        Ok(())
+    }
+
+    fn write_address(&mut self) -> Result {
+        if !self.has_ipv4_suffix() {
+            return self.write_hextets_with_ellipsis(8).map(|_| ());
+        }
+        // If the ellipsis ends at the sixth hextet, it already provides the column that separates
+        // the hextets from the IPv4 address.
+        if !self.write_hextets_with_ellipsis(6)? {
+            self.writer.write_str(":")?;
+        }
+        write!(
+            self.writer,
+            "{}.{}.{}.{}",
+            self.hextets[6] >> 8,
+            self.hextets[6] & 0xff,
+            self.hextets[7] >> 8,
+            self.hextets[7] & 0xff
+        )
+    }
+
+    /// Write the `len` first hextets, eliding the longest sequence of zeros if necessary. Return
+    /// `true` if the last thing written is an ellipsis.
+    fn write_hextets_with_ellipsis(&mut self, len: usize) -> ::core::result::Result<bool, Error> {
         if !self.ellipsis {
-            self.write_hextets(0..=7)
-        } else if let Some((start, end)) = self.longest_zero_sequence() {
+            self.write_hextets(0..len)?;
+            Ok(false)
+        } else if let Some((start, end)) = self.longest_zero_sequence(len) {
             self.write_hextets(0..start)?;
             self.writer.write_str("::")?;
-            self.write_hextets(end + 1..=7)
+            self.write_hextets(end + 1..len)?;
+            Ok(end + 1 == len)
         } else {
-            self.write_hextets(0..=7)
+            self.write_hextets(0..len)?;
+            Ok(false)
         }
     }
 
@@ -319,9 +483,9 @@ where
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
-    use ipv6::Ipv6Address;
+    use ipv6::{Ipv4Suffix, Ipv6Address};
 
     #[test]
     fn test_format_link_local() {
@@ -478,4 +642,114 @@ mod tests {
             .unwrap();
         assert_eq!(s, "0000:0000:0000:0000:0000:0000:0000:0001");
     }
+
+    #[test]
+    fn test_format_zero_sequences() {
+        let mut s = String::with_capacity(40);
+        let cases: [(u128, &str); 7] = [
+            // the longest sequence is the last one
+            (0x1400_0000_0000_0001_0000_0000_0000_0000, "1400:0:0:1::"),
+            (0x0001_0000_0002_0000_0000_0000_0003_0000, "1:0:2::3:0"),
+            // sequences separated by a single non-zero hextet are not merged
+            (0x0001_0000_0000_0002_0000_0003_0000_0000, "1::2:0:3:0:0"),
+            // the first sequence wins when they have the same length
+            (0x0001_0000_0000_0002_0003_0000_0000_0004, "1::2:3:0:0:4"),
+            (0x0000_0000_0001_0002_0003_0004_0000_0000, "::1:2:3:4:0:0"),
+            // a single zero hextet is not elided
+            (0x0001_0002_0003_0004_0000_0005_0006_0007, "1:2:3:4:0:5:6:7"),
+            (0x0001_0000_0002_0000_0003_0000_0004_0000, "1:0:2:0:3:0:4:0"),
+        ];
+        for &(ip, expected) in &cases {
+            s.truncate(0);
+            Ipv6Address(ip).formatter(&mut s).write().unwrap();
+            assert_eq!(s, expected);
+        }
+    }
+
+    #[test]
+    fn test_format_ipv4_suffix_auto() {
+        let mut s = String::with_capacity(40);
+        let cases: [(u128, &str); 8] = [
+            (0, "::"),
+            (1, "::1"),
+            (0xffff, "::ffff"),
+            (0x0102_0304, "::1.2.3.4"),
+            (0xffff_0102_0304, "::ffff:1.2.3.4"),
+            (0xffff_0000_0000, "::ffff:0.0.0.0"),
+            (
+                0x0064_ff9b_0000_0000_0000_0000_0102_0304,
+                "64:ff9b::1.2.3.4",
+            ),
+            (
+                0xfe80_0000_0000_0000_0000_ffff_0102_0304,
+                "fe80::ffff:102:304",
+            ),
+        ];
+        for &(ip, expected) in &cases {
+            s.truncate(0);
+            Ipv6Address(ip)
+                .formatter(&mut s)
+                .ipv4_suffix(Ipv4Suffix::Auto)
+                .write()
+                .unwrap();
+            assert_eq!(s, expected);
+        }
+    }
+
+    #[test]
+    fn test_format_ipv4_suffix_always() {
+        let mut s = String::with_capacity(50);
+        let cases: [(u128, &str); 6] = [
+            (0, "::0.0.0.0"),
+            (1, "::0.0.0.1"),
+            (0xffff_0102_0304, "::ffff:1.2.3.4"),
+            (0xfe80_0000_0000_0000_0000_0000_0000_0000, "fe80::0.0.0.0"),
+            (
+                0xfe80_0000_0000_0000_8657_e6fe_0102_0304,
+                "fe80::8657:e6fe:1.2.3.4",
+            ),
+            (
+                0x0001_0002_0003_0004_0005_0006_0102_0304,
+                "1:2:3:4:5:6:1.2.3.4",
+            ),
+        ];
+        for &(ip, expected) in &cases {
+            s.truncate(0);
+            Ipv6Address(ip)
+                .formatter(&mut s)
+                .ipv4_suffix(Ipv4Suffix::Always)
+                .write()
+                .unwrap();
+            assert_eq!(s, expected);
+        }
+
+        s.truncate(0);
+        Ipv6Address(0xffff_0102_0304)
+            .formatter(&mut s)
+            .ipv4_suffix(Ipv4Suffix::Always)
+            .expanded()
+            .upper_case(true)
+            .write()
+            .unwrap();
+        assert_eq!(s, "0000:0000:0000:0000:0000:FFFF:1.2.3.4");
+    }
+
+    #[test]
+    fn test_format_ipv4_suffix_round_trip() {
+        use core::str::FromStr;
+        let mut s = String::with_capacity(50);
+        for ip in &[
+            0xffff_0102_0304,
+            0x0102_0304,
+            0xfe80_0000_0000_0000_0000_0000_0102_0304,
+        ] {
+            s.truncate(0);
+            let ip = Ipv6Address(*ip);
+            ip.formatter(&mut s)
+                .ipv4_suffix(Ipv4Suffix::Always)
+                .write()
+                .unwrap();
+            assert_eq!(Ipv6Address::from_str(&s).unwrap(), ip);
+        }
+    }
 }
@@ -1,7 +1,7 @@
-use std::num::ParseIntError;
-use std::ops::*;
+use core::num::ParseIntError;
+use core::ops::*;
 
-use core::fmt::{self, Binary, Formatter, LowerHex, UpperHex};
+use core::fmt::{self, Binary, Formatter, LowerHex, Octal, UpperHex};
 
 use {Ipv6Address, Ipv6Mask};
 
@@ -1650,6 +1650,12 @@ impl Binary for Ipv6Address {
     }
 }
 
+impl Octal for Ipv6Address {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        <u128 as Octal>::fmt(&self.0, f)
+    }
+}
+
 impl From<u128> for Ipv6Address {
     fn from(value: u128) -> Self {
         Ipv6Address(value)
@@ -1,6 +1,8 @@
-use {Ipv4Address, Ipv6Address, ParsingFailed};
+use swar;
+use {Ipv4Address, Ipv6Address, ParseError, ParseOptions, ParsingFailed};
+use ParseErrorReason::*;
 
-use std::str::FromStr;
+use core::str::FromStr;
 
 impl FromStr for Ipv6Address {
     type Err = ParsingFailed;
@@ -9,236 +11,414 @@ impl FromStr for Ipv6Address {
         // We'll manipulate bytes instead of UTF-8 characters, because the characters that
         // represent an IPv6 address are supposed to be ASCII characters.
         let bytes = s.as_bytes();
+        if let Some(address) = swar::parse_ipv6(bytes) {
+            return Ok(Ipv6Address(address));
+        }
         Ipv6Address::parse(bytes)
-            .map_err(|()| ParsingFailed(s.into()))
+            .map_err(|e| ParsingFailed::new(s, e))
             .map(Ipv6Address)
     }
 }
 
 impl Ipv6Address {
-    fn parse(bytes: &[u8]) -> Result<u128, ()> {
-        // The maximimum length of a string representing an IPv6 is the length of:
-        //
-        //      1111:2222:3333:4444:5555:6666:123.123.123.123
-        //
-        // The minimum length of a string representing an IPv6 is the length of:
-        //
-        //      ::
-        //
-        if bytes.len() > 46 || bytes.len() < 2 {
-            return Err(());
+    /// Parse an IPv6 address, accepting only the syntaxes allowed by the given
+    /// [`ParseOptions`](struct.ParseOptions.html).
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv6Address, ParseOptions};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = Ipv6Address::from_str("fe80::1").unwrap();
+    ///
+    /// let options = ParseOptions::new().brackets(true).zone_ids(true);
+    /// assert_eq!(Ipv6Address::parse_with("[fe80::1]", &options).unwrap(), ip);
+    /// assert_eq!(Ipv6Address::parse_with("fe80::1%eth0", &options).unwrap(), ip);
+    /// assert_eq!(Ipv6Address::parse_with("[fe80::1%eth0]", &options).unwrap(), ip);
+    ///
+    /// let options = ParseOptions::new().uppercase_hex(false).embedded_ipv4(false);
+    /// assert!(Ipv6Address::parse_with("FE80::1", &options).is_err());
+    /// assert!(Ipv6Address::parse_with("::ffff:192.0.2.33", &options).is_err());
+    /// # }
+    /// ```
+    pub fn parse_with(s: &str, options: &ParseOptions) -> Result<Self, ParsingFailed> {
+        let (start, bytes) = options.trim(s.as_bytes());
+        options
+            .strip_ipv6_decorations(bytes)
+            .and_then(|(offset, bytes)| {
+                Self::parse_with_options(bytes, options).map_err(|e| e.shift(offset))
+            })
+            .map_err(|e| ParsingFailed::new(s, e.shift(start)))
+            .map(Ipv6Address)
+    }
+
+    /// Parse an IPv6 address from ASCII bytes. This is the same as `from_str()`, but the input
+    /// does not need to be valid UTF-8.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// assert_eq!(
+    ///     Ipv6Address::from_ascii(b"fe80::1").unwrap(),
+    ///     Ipv6Address::from_str("fe80::1").unwrap()
+    /// );
+    /// assert!(Ipv6Address::from_ascii(b"fe80::\xff").is_err());
+    /// # }
+    /// ```
+    pub fn from_ascii(bytes: &[u8]) -> Result<Self, ParsingFailed> {
+        if let Some(address) = swar::parse_ipv6(bytes) {
+            return Ok(Ipv6Address(address));
         }
+        Self::parse(bytes)
+            .map_err(|e| ParsingFailed::from_bytes(bytes, e))
+            .map(Ipv6Address)
+    }
 
-        let mut offset = 0;
-        let mut ellipsis: Option<usize> = None;
+    /// Parse an IPv6 address at the beginning of the given bytes, and return it along with the
+    /// number of bytes that were read. The bytes that follow the address are not checked, which
+    /// is useful to scan a buffer without splitting it first.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let (ip, len) = Ipv6Address::parse_prefix(b"fe80::1%eth0").unwrap();
+    /// assert_eq!(ip, Ipv6Address::from_str("fe80::1").unwrap());
+    /// assert_eq!(len, 7);
+    ///
+    /// // a trailing column is not part of the address
+    /// let (ip, len) = Ipv6Address::parse_prefix(b"::ffff:1.2.3.4: connection refused").unwrap();
+    /// assert_eq!(ip, Ipv6Address::from_str("::ffff:1.2.3.4").unwrap());
+    /// assert_eq!(len, 14);
+    /// # }
+    /// ```
+    pub fn parse_prefix(bytes: &[u8]) -> Result<(Self, usize), ParsingFailed> {
+        Self::parse_prefix_with_options(bytes, &ParseOptions::new())
+            .map_err(|e| ParsingFailed::from_bytes(bytes, e))
+            .map(|(ip, len)| (Ipv6Address(ip), len))
+    }
 
-        // Handle the special case where the IP start with "::"
-        if bytes[0] == b':' {
-            if bytes[1] == b':' {
-                if bytes.len() == 2 {
-                    return Ok(0);
-                }
-                ellipsis = Some(0);
-                offset += 2;
-            } else {
-                // An IPv6 cannot start with a single column. It must be a double column.
-                // So this is an invalid address
-                return Err(());
-            }
+    /// Parse an IPv6 address at compile time. This is meant to be used in constants: an invalid
+    /// string is then a build error. The [`ipv6!`](macro.ipv6.html) macro is a shortcut for
+    /// this.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the string is not a valid IPv6 address.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// const VALUE: Ipv6Address = Ipv6Address::parse_const("2001:db8::1");
+    /// assert_eq!(VALUE, Ipv6Address::from_str("2001:db8::1").unwrap());
+    /// # }
+    /// ```
+    ///
+    /// ```rust,compile_fail
+    /// # use ipaddr::Ipv6Address;
+    /// const VALUE: Ipv6Address = Ipv6Address::parse_const("2001:db8::1::2");
+    /// ```
+    pub const fn parse_const(s: &str) -> Self {
+        match Ipv6Address::parse(s.as_bytes()) {
+            Ok(value) => Ipv6Address(value),
+            Err(e) => panic!("{}", e.reason().description()),
         }
+    }
 
-        // When dealing with IPv6, it's easier to reason in terms of "hextets" instead of octets.
-        // An IPv6 is 8 hextets. At the end, we'll convert that array into an u128.
-        let mut address: [u16; 8] = [0; 8];
+    pub(crate) const fn parse(bytes: &[u8]) -> Result<u128, ParseError> {
+        Self::parse_with_options(bytes, &ParseOptions::new())
+    }
 
-        // Keep track of the number of hextets we process
-        let mut hextet_index = 0;
+    // The parsing functions are `const fn`, so that addresses can be parsed at compile time.
+    // This is why they use `while` loops and `match` instead of iterators and `?`.
 
-        loop {
-            if offset == bytes.len() {
-                break;
-            }
+    pub(crate) const fn parse_with_options(
+        bytes: &[u8],
+        options: &ParseOptions,
+    ) -> Result<u128, ParseError> {
+        match parse(bytes, options, false) {
+            Ok((address, _)) => Ok(address),
+            Err(e) => Err(e),
+        }
+    }
 
-            // Try to read an hextet
-            let (bytes_read, hextet) = read_hextet(&bytes[offset..]);
-
-            // Handle the case where we could not read an hextet
-            if bytes_read == 0 {
-                match bytes[offset] {
-                    // We could not read an hextet because the first character in the slace was ":"
-                    // This may be because we have two consecutive columns.
-                    b':' => {
-                        // Check if already saw an ellipsis. If so, fail parsing, because an IPv6
-                        // can only have one ellipsis.
-                        if ellipsis.is_some() {
-                            return Err(());
-                        }
-                        // Otherwise, remember the position of the ellipsis. We'll need that later
-                        // to count the number of zeros the ellipsis represents.
-                        ellipsis = Some(hextet_index);
-                        offset += 1;
-                        // Continue and try to read the next hextet
-                        continue;
-                    }
-                    // We now the first character does not represent an hexadecimal digit
-                    // (otherwise read_hextet() would have read at least one character), and that
-                    // it's not ":", so the string does not represent an IPv6 address
-                    _ => return Err(()),
-                }
-            }
+    pub(crate) const fn parse_prefix_with_options(
+        bytes: &[u8],
+        options: &ParseOptions,
+    ) -> Result<(u128, usize), ParseError> {
+        parse(bytes, options, true)
+    }
+}
 
-            // At this point, we know we read an hextet.
+/// Parse an IPv6 address and return it along with the number of bytes that were read. If `prefix`
+/// is `true`, parsing stops at the first byte that cannot be part of the address, instead of
+/// failing.
+const fn parse(
+    bytes: &[u8],
+    options: &ParseOptions,
+    prefix: bool,
+) -> Result<(u128, usize), ParseError> {
+    if bytes.is_empty() {
+        return Err(ParseError::new(0, Empty));
+    }
 
-            address[hextet_index] = hextet;
-            offset += bytes_read;
-            hextet_index += 1;
+    let mut offset = 0;
+    // Position of the ellipsis, in hextets and in bytes
+    let mut ellipsis: Option<(usize, usize)> = None;
 
-            // If this was the last hextet of if we reached the end of the buffer, we should be
-            // done
-            if hextet_index == 8 || offset == bytes.len() {
-                break;
+    // Handle the special case where the IP start with "::"
+    if bytes[0] == b':' {
+        if bytes.len() > 1 && bytes[1] == b':' {
+            if bytes.len() == 2 {
+                return Ok((0, 2));
             }
+            ellipsis = Some((0, 0));
+            offset += 2;
+        } else {
+            // An IPv6 cannot start with a single column. It must be a double column.
+            // So this is an invalid address
+            return Err(ParseError::new(0, SingleColon));
+        }
+    }
+
+    // When dealing with IPv6, it's easier to reason in terms of "hextets" instead of octets.
+    // An IPv6 is 8 hextets. At the end, we'll convert that array into an u128.
+    let mut address: [u16; 8] = [0; 8];
+
+    // Keep track of the number of hextets we process
+    let mut hextet_index = 0;
+
+    loop {
+        if offset == bytes.len() {
+            break;
+        }
+
+        // Try to read an hextet
+        let (bytes_read, hextet) = read_hextet(bytes, offset, options.uppercase_hex);
 
-            // Read the next charachter. After a hextet, we usually expect a column, but there's a special
-            // case for IPv6 that ends with an IPv4.
+        // Handle the case where we could not read an hextet
+        if bytes_read == 0 {
             match bytes[offset] {
-                // We saw the column, we can continue
+                // We could not read an hextet because the first character in the slace was ":"
+                // This may be because we have two consecutive columns.
                 b':' => {
-                    offset += 1;
-                    if offset == bytes.len() {
-                        // We cannot terminate with a single column
-                        return Err(());
+                    // Check if already saw an ellipsis. If so, fail parsing, because an IPv6
+                    // can only have one ellipsis.
+                    if let Some((_, ellipsis_offset)) = ellipsis {
+                        // If the previous ellipsis ends right before this column, we have three
+                        // consecutive columns, and the error is on this one.
+                        let error_offset = if ellipsis_offset + 2 == offset {
+                            offset
+                        } else {
+                            offset - 1
+                        };
+                        return Err(ParseError::new(error_offset, DoubleEllipsis));
                     }
+                    // Otherwise, remember the position of the ellipsis. We'll need that later
+                    // to count the number of zeros the ellipsis represents.
+                    ellipsis = Some((hextet_index, offset - 1));
+                    offset += 1;
+                    // Continue and try to read the next hextet
+                    continue;
                 }
-                // Handle the special IPv4 case, ie address like. Note that the hextet we just read
-                // is part of that IPv4 address:
+                // We now the first character does not represent an hexadecimal digit
+                // (otherwise read_hextet() would have read at least one character), and that
+                // it's not ":", so the string does not represent an IPv6 address
                 //
-                // aaaa:bbbb:cccc:dddd:eeee:ffff:a.b.c.d.
-                //                               ^^
-                //                               ||
-                // hextet we just read, that  ---+|
-                // is actually the first byte of  +--- dot we're handling
-                // the ipv4.
-                b'.' => {
-                    // The hextet was actually part of the IPv4, so not that we start reading the
-                    // IPv4 at `offset - bytes_read`.
-                    let ipv4: u32 = Ipv4Address::parse(&bytes[offset - bytes_read..])?;
-                    // Important: is Ipv4Address succeeds, we know we reached the end of the
-                    // buffer! If there were trailing characters, the method would fail! We set
-                    // the offset because we have a check later to make sure the offset is equal to
-                    // the number of bytes in the buffer.
-                    offset = bytes.len();
-                    // Replace the hextet we just read by the 16 most significant bits of the
-                    // IPv4 address (a.b in the comment above)
-                    address[hextet_index - 1] = ((ipv4 & 0xffff_0000) >> 16) as u16;
-                    // Set the last hextet to the 16 least significant bits of the IPv4 address
-                    // (c.d in the comment above)
-                    address[hextet_index] = (ipv4 & 0x0000_ffff) as u16;
-                    hextet_index += 1;
-                    // After successfully parsing an IPv4, we should be done.
-                    // If there are bytes left in the buffer, or if we didn't read enough hextet,
-                    // we'll fail later.
+                // When parsing a prefix, this is the end of the address. If the previous
+                // column was not part of an ellipsis, it does not belong to the address.
+                _ if prefix && offset > 0 => {
+                    let after_ellipsis = match ellipsis {
+                        Some((_, start)) => start + 2 == offset,
+                        None => false,
+                    };
+                    if !after_ellipsis {
+                        offset -= 1;
+                    }
                     break;
                 }
-                _ => return Err(()),
+                _ => return Err(ParseError::new(offset, InvalidCharacter)),
             }
-        } // end of loop
+        }
+
+        // At this point, we know we read an hextet.
+
+        address[hextet_index] = hextet;
+        offset += bytes_read;
+        hextet_index += 1;
 
-        // If we exited the loop, we should have reached the end of the buffer.
-        // If there are trailing characters, parsing should fail.
-        if offset < bytes.len() {
-            return Err(());
+        if offset < bytes.len() && is_hex_digit(bytes[offset], options.uppercase_hex) {
+            // read_hextet() reads at most four digits
+            return Err(ParseError::new(offset, TooManyDigits));
         }
 
-        if hextet_index == 8 && ellipsis.is_some() {
-            // We parsed an address that looks like 1111:2222::3333:4444:5555:6666:7777,
-            // ie with an empty ellipsis.
-            return Err(());
+        // If this was the last hextet of if we reached the end of the buffer, we should be
+        // done
+        if hextet_index == 8 || offset == bytes.len() {
+            break;
         }
 
-        // We didn't parse enough hextets, but this may be due to an ellipsis
-        if hextet_index < 8 {
-            if let Some(ellipsis_index) = ellipsis {
-                // Count how many zeros the ellipsis accounts for
-                let nb_zeros = 8 - hextet_index;
-                // Shift the hextet that we read after the ellipsis by the number of zeros
-                for index in (ellipsis_index..hextet_index).rev() {
-                    address[index + nb_zeros] = address[index];
-                    address[index] = 0;
+        // Read the next charachter. After a hextet, we usually expect a column, but there's a special
+        // case for IPv6 that ends with an IPv4.
+        match bytes[offset] {
+            // We saw the column, we can continue
+            b':' => {
+                offset += 1;
+                if offset == bytes.len() {
+                    if prefix {
+                        // The column does not belong to the address
+                        offset -= 1;
+                        break;
+                    }
+                    // We cannot terminate with a single column
+                    return Err(ParseError::new(offset - 1, SingleColon));
                 }
-            } else {
-                return Err(());
             }
+            // Handle the special IPv4 case, ie address like. Note that the hextet we just read
+            // is part of that IPv4 address:
+            //
+            // aaaa:bbbb:cccc:dddd:eeee:ffff:a.b.c.d.
+            //                               ^^
+            //                               ||
+            // hextet we just read, that  ---+|
+            // is actually the first byte of  +--- dot we're handling
+            // the ipv4.
+            b'.' if options.embedded_ipv4 => {
+                // The hextet was actually part of the IPv4, so not that we start reading the
+                // IPv4 at `offset - bytes_read`.
+                let start = offset - bytes_read;
+                let ipv4: u32 = if prefix {
+                    // If there's no IPv4 address, the dot is simply not part of the address.
+                    match Ipv4Address::parse_prefix_with_options(bytes.split_at(start).1, options) {
+                        Ok((ipv4, len)) => {
+                            offset = start + len;
+                            ipv4
+                        }
+                        Err(_) => break,
+                    }
+                } else {
+                    let ipv4 = match Ipv4Address::parse_with_options(bytes.split_at(start).1, options) {
+                        Ok(ipv4) => ipv4,
+                        Err(e) => {
+                            // Leading zeros are rejected by an option, so report them as such
+                            let reason = match e.reason() {
+                                LeadingZero => LeadingZero,
+                                _ => InvalidEmbeddedIpv4,
+                            };
+                            return Err(ParseError::new(e.offset() + start, reason));
+                        }
+                    };
+                    // Important: is Ipv4Address succeeds, we know we reached the end of the
+                    // buffer! If there were trailing characters, the method would fail! We
+                    // set the offset because we have a check later to make sure the offset
+                    // is equal to the number of bytes in the buffer.
+                    offset = bytes.len();
+                    ipv4
+                };
+                // Replace the hextet we just read by the 16 most significant bits of the
+                // IPv4 address (a.b in the comment above)
+                address[hextet_index - 1] = ((ipv4 & 0xffff_0000) >> 16) as u16;
+                // Set the last hextet to the 16 least significant bits of the IPv4 address
+                // (c.d in the comment above)
+                address[hextet_index] = (ipv4 & 0x0000_ffff) as u16;
+                hextet_index += 1;
+                // After successfully parsing an IPv4, we should be done.
+                // If there are bytes left in the buffer, or if we didn't read enough hextet,
+                // we'll fail later.
+                break;
+            }
+            _ if prefix => break,
+            _ => return Err(ParseError::new(offset, InvalidCharacter)),
         }
+    } // end of loop
+
+    // If we exited the loop, we should have reached the end of the buffer.
+    // If there are trailing characters, parsing should fail.
+    if !prefix && offset < bytes.len() {
+        return match bytes[offset] {
+            // We already read 8 hextets, and there's at least one more
+            b':' | b'.' => Err(ParseError::new(offset, TooManyHextets)),
+            _ => Err(ParseError::new(offset, TrailingCharacters)),
+        };
+    }
 
-        // Build the IPv6 address from the array of hextets
-        Ok((u128::from(address[0]) << 112)
-            + (u128::from(address[1]) << 96)
-            + (u128::from(address[2]) << 80)
-            + (u128::from(address[3]) << 64)
-            + (u128::from(address[4]) << 48)
-            + (u128::from(address[5]) << 32)
-            + (u128::from(address[6]) << 16)
-            + u128::from(address[7]))
+    if let (8, Some((_, ellipsis_offset))) = (hextet_index, ellipsis) {
+        // We parsed an address that looks like 1111:2222::3333:4444:5555:6666:7777,
+        // ie with an empty ellipsis.
+        return Err(ParseError::new(ellipsis_offset, EmptyEllipsis));
     }
+
+    // We didn't parse enough hextets, but this may be due to an ellipsis
+    if hextet_index < 8 {
+        if let Some((ellipsis_index, _)) = ellipsis {
+            // Count how many zeros the ellipsis accounts for
+            let nb_zeros = 8 - hextet_index;
+            // Shift the hextet that we read after the ellipsis by the number of zeros
+            let mut index = hextet_index;
+            while index > ellipsis_index {
+                index -= 1;
+                address[index + nb_zeros] = address[index];
+                address[index] = 0;
+            }
+        } else {
+            return Err(ParseError::new(offset, TooFewHextets));
+        }
+    }
+
+    // Build the IPv6 address from the array of hextets
+    Ok((
+        ((address[0] as u128) << 112)
+            + ((address[1] as u128) << 96)
+            + ((address[2] as u128) << 80)
+            + ((address[3] as u128) << 64)
+            + ((address[4] as u128) << 48)
+            + ((address[5] as u128) << 32)
+            + ((address[6] as u128) << 16)
+            + address[7] as u128,
+        offset,
+    ))
 }
 
-/// Check whether an ASCII character represents an hexadecimal digit
-fn is_hex_digit(byte: u8) -> bool {
+/// Check whether an ASCII character represents an hexadecimal digit. Upper case digits are only
+/// accepted if `uppercase` is `true`.
+const fn is_hex_digit(byte: u8, uppercase: bool) -> bool {
     match byte {
-        b'0'...b'9' | b'a'...b'f' | b'A'...b'F' => true,
+        b'0'..=b'9' | b'a'..=b'f' => true,
+        b'A'..=b'F' => uppercase,
         _ => false,
     }
 }
 
 /// Convert an ASCII character that represents an hexadecimal digit into this digit
-fn hex_to_digit(byte: u8) -> u8 {
+const fn hex_to_digit(byte: u8) -> u8 {
     match byte {
-        b'0'...b'9' => byte - b'0',
-        b'a'...b'f' => byte - b'a' + 10,
-        b'A'...b'F' => byte - b'A' + 10,
+        b'0'..=b'9' => byte - b'0',
+        b'a'..=b'f' => byte - b'a' + 10,
+        b'A'..=b'F' => byte - b'A' + 10,
         _ => unreachable!(),
     }
 }
 
-/// Read up to four ASCII characters that represent hexadecimal digits, and return their value, as
-/// well as the number of characters that were read. If not character is read, `(0, 0)` is
-/// returned.
-fn read_hextet(bytes: &[u8]) -> (usize, u16) {
+/// Read up to four ASCII characters that represent hexadecimal digits, starting at `offset`, and
+/// return their value, as well as the number of characters that were read. If not character is
+/// read, `(0, 0)` is returned. Upper case digits are only accepted if `uppercase` is `true`.
+const fn read_hextet(bytes: &[u8], offset: usize, uppercase: bool) -> (usize, u16) {
     let mut count = 0;
-    let mut digits: [u8; 4] = [0; 4];
-
-    for b in bytes {
-        if is_hex_digit(*b) {
-            digits[count] = hex_to_digit(*b);
-            count += 1;
-            if count == 4 {
-                break;
-            }
-        } else {
-            break;
-        }
-    }
-
-    if count == 0 {
-        return (0, 0);
-    }
-
-    let mut shift = (count - 1) * 4;
-    let mut res = 0;
-    for digit in &digits[0..count] {
-        res += u16::from(*digit) << shift;
-        if shift >= 4 {
-            shift -= 4;
-        } else {
-            break;
-        }
+    let mut res: u16 = 0;
+
+    while count < 4
+        && offset + count < bytes.len()
+        && is_hex_digit(bytes[offset + count], uppercase)
+    {
+        res = (res << 4) + hex_to_digit(bytes[offset + count]) as u16;
+        count += 1;
     }
 
     (count, res)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests_ipv6 {
     use super::*;
 
@@ -326,4 +506,71 @@ mod tests_ipv6 {
         assert!(Ipv6Address::from_str("1fe80:0000:0000:0000:8657:e6fe:255.255.255.255").is_err());
         assert!(Ipv6Address::from_str("fe80:0000:0000:0000:8657:e6fe:1234").is_err());
     }
+
+    fn error(s: &str) -> (usize, ::ParseErrorReason) {
+        let err = Ipv6Address::from_str(s).unwrap_err();
+        assert_eq!(err.input(), s);
+        (err.offset(), err.reason())
+    }
+
+    #[test]
+    fn test_error_details() {
+        assert_eq!(error(""), (0, Empty));
+        assert_eq!(error(":"), (0, SingleColon));
+        assert_eq!(error(":ffff::"), (0, SingleColon));
+        assert_eq!(error("::ffff:"), (6, SingleColon));
+        assert_eq!(error("  "), (0, InvalidCharacter));
+        assert_eq!(error(":: "), (2, InvalidCharacter));
+        assert_eq!(error("fe80::g"), (6, InvalidCharacter));
+        assert_eq!(error("fe80:-1::"), (5, InvalidCharacter));
+        assert_eq!(error("::ffff "), (6, InvalidCharacter));
+        assert_eq!(error("1:2:3:4:5:6:7:8 "), (15, TrailingCharacters));
+        assert_eq!(error("::::"), (2, DoubleEllipsis));
+        assert_eq!(error("::1::"), (3, DoubleEllipsis));
+        assert_eq!(error("ffff::1::"), (7, DoubleEllipsis));
+        assert_eq!(error("1:::2"), (3, DoubleEllipsis));
+        assert_eq!(error("1:2:3:4::5:6:7:8"), (7, EmptyEllipsis));
+        assert_eq!(error("1fe80::"), (4, TooManyDigits));
+        assert_eq!(error("1:2:3:4:5:6:7:00008"), (18, TooManyDigits));
+        assert_eq!(error("1:2:3:4:5:6:7:8:9"), (15, TooManyHextets));
+        assert_eq!(error("1:2:3:4:5:6:7:1.2.3.4"), (15, TooManyHextets));
+        assert_eq!(error("1:2:3:4:5:6:7"), (13, TooFewHextets));
+        assert_eq!(error("fe80:0000:0000:0000:8657:e6fe:1234"), (34, TooFewHextets));
+        assert_eq!(error("::1.2.3.256"), (8, InvalidEmbeddedIpv4));
+        assert_eq!(error("::1.2.3"), (7, InvalidEmbeddedIpv4));
+        assert_eq!(error("::1.2.3.4:5"), (9, InvalidEmbeddedIpv4));
+    }
+
+    #[test]
+    fn test_parse_prefix() {
+        let prefix = |s: &str| {
+            let (ip, len) = Ipv6Address::parse_prefix(s.as_bytes()).unwrap();
+            (ip, len)
+        };
+        let ip = |s| Ipv6Address::from_str(s).unwrap();
+        assert_eq!(prefix("::"), (ip("::"), 2));
+        assert_eq!(prefix(":: "), (ip("::"), 2));
+        assert_eq!(prefix("::1 "), (ip("::1"), 3));
+        assert_eq!(prefix("::1:"), (ip("::1"), 3));
+        assert_eq!(prefix("::1: foo"), (ip("::1"), 3));
+        assert_eq!(prefix("fe80::%1"), (ip("fe80::"), 6));
+        assert_eq!(prefix("fe80::1]:80"), (ip("fe80::1"), 7));
+        assert_eq!(prefix("1:2:3:4:5:6:7:8:9"), (ip("1:2:3:4:5:6:7:8"), 15));
+        assert_eq!(prefix("::ffff:1.2.3.4.5"), (ip("::ffff:1.2.3.4"), 14));
+        assert_eq!(prefix("::1."), (ip("::1"), 3));
+        assert_eq!(prefix("::1.2"), (ip("::1"), 3));
+        assert!(Ipv6Address::parse_prefix(b"").is_err());
+        assert!(Ipv6Address::parse_prefix(b" ::1").is_err());
+        assert!(Ipv6Address::parse_prefix(b"1:2:3:4:5:6:7 ").is_err());
+        assert!(Ipv6Address::parse_prefix(b"1:2:3:4:5:6:7:").is_err());
+        assert!(Ipv6Address::parse_prefix(b"fe80::12345").is_err());
+    }
+
+    #[test]
+    fn test_from_ascii() {
+        assert_eq!(Ipv6Address::from_ascii(b"::1").unwrap(), Ipv6Address(1));
+        let err = Ipv6Address::from_ascii(b"::\xff").unwrap_err();
+        assert_eq!(err.input(), "::\u{fffd}");
+        assert_eq!((err.offset(), err.reason()), (2, InvalidCharacter));
+    }
 }
@@ -1,21 +1,30 @@
 #![cfg_attr(nightly, allow(unused_attributes))]
 #![cfg_attr(nightly, rustfmt::skip)]
 
-use Ipv6Address;
+use {Ipv6Address, Ipv6Network};
 
 /// IPv6 unspecified address: `::`
-pub const IPV6_UNSPECIFIED: Ipv6Address = Ipv6Address(0);
+pub const IPV6_UNSPECIFIED: Ipv6Address = Ipv6Address::parse_const("::");
 
 /// IPv6 loopback address: `::1`
-pub const IPV6_LOOPBACK: Ipv6Address = Ipv6Address(1);
+pub const IPV6_LOOPBACK: Ipv6Address = Ipv6Address::parse_const("::1");
 
 /// Multicast address for all the address on the interface: `ff01::1`
-pub const IPV6_INTERFACE_LOCAL_ALL_NODES: Ipv6Address = Ipv6Address(0xff01_0000_0000_0000_0000_0000_0000_0001);
+pub const IPV6_INTERFACE_LOCAL_ALL_NODES: Ipv6Address = Ipv6Address::parse_const("ff01::1");
 
 /// Multicast address for all the nodes on the network segment: `ff02::1`
-pub const IPV6_LINK_LOCAL_ALL_NODES: Ipv6Address = Ipv6Address(0xff02_0000_0000_0000_0000_0000_0000_0001);
+pub const IPV6_LINK_LOCAL_ALL_NODES: Ipv6Address = Ipv6Address::parse_const("ff02::1");
 
 /// Multicast address for all the routers on the network segment: `ff02::2`
-pub const IPV6_LINK_LOCAL_ALL_ROUTERS: Ipv6Address = Ipv6Address(0xff02_0000_0000_0000_0000_0000_0000_0002);
+pub const IPV6_LINK_LOCAL_ALL_ROUTERS: Ipv6Address = Ipv6Address::parse_const("ff02::2");
+
+/// NAT64 well-known prefix: `64:ff9b::/96` (see [RFC6052](https://tools.ietf.org/html/rfc6052#section-2.1))
+pub const IPV6_NAT64_WELL_KNOWN_PREFIX: Ipv6Network = Ipv6Network::parse_const("64:ff9b::/96");
+
+/// 6to4 prefix: `2002::/16` (see [RFC3056](https://tools.ietf.org/html/rfc3056#section-2))
+pub const IPV6_6TO4_PREFIX: Ipv6Network = Ipv6Network::parse_const("2002::/16");
+
+/// Teredo prefix: `2001::/32` (see [RFC4380](https://tools.ietf.org/html/rfc4380#section-2.6))
+pub const IPV6_TEREDO_PREFIX: Ipv6Network = Ipv6Network::parse_const("2001::/32");
 
 pub(crate) const IPV6_MAX_PREFIXLEN: u8 = 128;
@@ -1,18 +1,21 @@
-use core::fmt::{self, Binary, Formatter, LowerHex, UpperHex};
-use std::convert::TryFrom;
-use std::ops::{BitAnd, Not};
-use std::str::FromStr;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt::{self, Binary, Formatter, LowerHex, Octal, UpperHex};
+use core::convert::TryFrom;
+use core::ops::{BitAnd, Not};
+use core::str::FromStr;
 
-use {IPV6_MAX_PREFIXLEN, InvalidMask, Ipv6Address, Ipv6Formatter, ParsingFailed};
+use {IPV6_MAX_PREFIXLEN, InvalidMask, Ipv6Address, Ipv6Formatter, Ipv6StrBuf, ParseError,
+     ParseErrorReason, ParsingFailed};
 
 /// Check whether the given integer represents a valid IPv6 mask.
 // see https://codereview.stackexchange.com/a/197138/118470
-fn is_valid_mask(value: u128) -> bool {
+const fn is_valid_mask(value: u128) -> bool {
     value.count_zeros() == value.trailing_zeros()
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
-pub struct Ipv6Mask(u128);
+pub struct Ipv6Mask(pub(crate) u128);
 
 impl Ipv6Mask {
     /// Return the prefix length that correspond to this mask.
@@ -38,7 +41,7 @@ impl Ipv6Mask {
     /// assert_eq!(u128::from(mask), 0xffff_ffff_ffff_ffff_0000_0000_0000_0000);
     /// # }
     /// ```
-    pub fn from_prefixlen(prefixlen: u8) -> Result<Self, InvalidMask> {
+    pub const fn from_prefixlen(prefixlen: u8) -> Result<Self, InvalidMask> {
         match prefixlen {
             IPV6_MAX_PREFIXLEN => Ok(Ipv6Mask(u128::max_value())),
             0 => Ok(Ipv6Mask(0)),
@@ -77,6 +80,7 @@ impl Ipv6Mask {
     /// assert_eq!(format!("{}", mask), "ffff:ffff:ffff:ffff::");
     /// # }
     /// ```
+    #[cfg(feature = "alloc")]
     pub fn to_string(&self) -> String {
         let mut s = String::with_capacity(40);
         // https://doc.rust-lang.org/std/fmt/index.html#formatting-traits
@@ -88,11 +92,23 @@ impl Ipv6Mask {
             .expect("string formatting failed?!");
         s
     }
+
+    /// Return the same representation as [`to_string()`](#method.to_string), in a buffer
+    /// allocated on the stack.
+    pub fn to_str_buf(&self) -> Ipv6StrBuf {
+        let mut buf = Ipv6StrBuf::new();
+        // The longest IPv6 mask is 45 bytes long, so this cannot fail
+        self.formatter(&mut buf)
+            .rfc_5952()
+            .write()
+            .expect("Ipv6StrBuf is too small?!");
+        buf
+    }
 }
 
 impl fmt::Display for Ipv6Mask {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.formatter(f).write()
+        fmt::Display::fmt(&Ipv6Address::from(self), f)
     }
 }
 
@@ -192,6 +208,12 @@ impl Binary for Ipv6Mask {
     }
 }
 
+impl Octal for Ipv6Mask {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        <u128 as Octal>::fmt(&self.0, f)
+    }
+}
+
 impl BitAnd<Ipv6Mask> for Ipv6Address {
     type Output = Ipv6Address;
     fn bitand(self, rhs: Ipv6Mask) -> Self::Output {
@@ -224,8 +246,63 @@ impl FromStr for Ipv6Mask {
     type Err = ParsingFailed;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let ip = Ipv6Address::from_str(s)?;
-        Ipv6Mask::try_from(ip).map_err(|_| ParsingFailed(s.into()))
+        Ipv6Mask::parse(s.as_bytes()).map_err(|e| ParsingFailed::new(s, e))
+    }
+}
+
+impl Ipv6Mask {
+    /// Parse a mask from ASCII bytes. This is the same as `from_str()`, but the input does not
+    /// need to be valid UTF-8.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Mask;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// assert_eq!(
+    ///     Ipv6Mask::from_ascii(b"ffff:ffff::").unwrap(),
+    ///     Ipv6Mask::from_str("ffff:ffff::").unwrap()
+    /// );
+    /// assert!(Ipv6Mask::from_ascii(b"ffff::ffff").is_err());
+    /// # }
+    /// ```
+    pub fn from_ascii(bytes: &[u8]) -> Result<Self, ParsingFailed> {
+        Ipv6Mask::parse(bytes).map_err(|e| ParsingFailed::from_bytes(bytes, e))
+    }
+
+    /// Parse an IPv6 mask at compile time. This is meant to be used in constants: an invalid
+    /// string is then a build error. The [`ipv6_mask!`](macro.ipv6_mask.html) macro is a shortcut for
+    /// this.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the string is not a valid IPv6 mask.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Mask;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// const VALUE: Ipv6Mask = Ipv6Mask::parse_const("ffff:ffff::");
+    /// assert_eq!(VALUE, Ipv6Mask::from_prefixlen(32).unwrap());
+    /// # }
+    /// ```
+    ///
+    /// ```rust,compile_fail
+    /// # use ipaddr::Ipv6Mask;
+    /// const VALUE: Ipv6Mask = Ipv6Mask::parse_const("ffff::ffff");
+    /// ```
+    pub const fn parse_const(s: &str) -> Self {
+        match Ipv6Mask::parse(s.as_bytes()) {
+            Ok(value) => value,
+            Err(e) => panic!("{}", e.reason().description()),
+        }
+    }
+
+    pub(crate) const fn parse(bytes: &[u8]) -> Result<Self, ParseError> {
+        match Ipv6Address::parse(bytes) {
+            Ok(value) if is_valid_mask(value) => Ok(Ipv6Mask(value)),
+            Ok(_) => Err(ParseError::new(0, ParseErrorReason::InvalidMask)),
+            Err(e) => Err(e),
+        }
     }
 }
 
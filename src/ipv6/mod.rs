@@ -6,3 +6,10 @@ mod mask;
 pub use self::mask::*;
 mod constants;
 pub use self::constants::*;
+mod nat64;
+mod tunnel;
+pub use self::tunnel::*;
+#[cfg(feature = "alloc")]
+mod scoped;
+#[cfg(feature = "alloc")]
+pub use self::scoped::*;
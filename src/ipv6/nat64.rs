@@ -0,0 +1,209 @@
+//! IPv4-embedded IPv6 addresses, as described in [RFC6052](https://tools.ietf.org/html/rfc6052).
+
+use {InvalidNat64Prefix, Ipv4Address, Ipv6Address, Ipv6Network, IPV6_NAT64_WELL_KNOWN_PREFIX};
+
+/// Bits 64 to 71 of an IPv4-embedded IPv6 address (the "u" octet) are reserved and must be zero.
+/// This is the number of bits that follow the u octet.
+const SUFFIX_LEN: u32 = 56;
+/// Number of bits that follow the first bit of the u octet.
+const U_OCTET_OFFSET: u32 = 64;
+
+impl Ipv6Network {
+    /// Embed an IPv4 address in this NAT64 prefix, as described in
+    /// [RFC6052](https://tools.ietf.org/html/rfc6052#section-2.2).
+    ///
+    /// The prefix length must be 32, 40, 48, 56, 64 or 96. The IPv4 address is written right
+    /// after the prefix, and bits 64 to 71 (the "u" octet) are skipped and left to zero:
+    ///
+    /// ```no_rust
+    /// +--+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+
+    /// |PL| 0-------------32--40--48--56--64--72--80--88--96--104---------|
+    /// +--+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+
+    /// |32|     prefix    |v4(32)         | u | suffix                    |
+    /// +--+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+
+    /// |40|     prefix        |v4(24)     | u |(8)| suffix                |
+    /// +--+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+
+    /// |48|     prefix            |v4(16) | u | (16)  | suffix            |
+    /// +--+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+
+    /// |56|     prefix                |(8)| u |  v4(24)   | suffix        |
+    /// +--+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+
+    /// |64|     prefix                    | u |   v4(32)      | suffix    |
+    /// +--+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+
+    /// |96|     prefix                                    |    v4(32)     |
+    /// +--+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+
+    /// ```
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv6Address, Ipv6Network};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ipv4 = Ipv4Address::from_str("192.0.2.33").unwrap();
+    ///
+    /// let prefix = Ipv6Network::from_str("2001:db8:122::/48").unwrap();
+    /// let ipv6 = prefix.embed_ipv4(ipv4).unwrap();
+    /// assert_eq!(ipv6, Ipv6Address::from_str("2001:db8:122:c000:2:2100::").unwrap());
+    ///
+    /// // /36 is not a valid NAT64 prefix length
+    /// let prefix = Ipv6Network::from_str("2001:db8::/36").unwrap();
+    /// assert!(prefix.embed_ipv4(ipv4).is_err());
+    /// # }
+    /// ```
+    pub fn embed_ipv4(&self, ipv4: Ipv4Address) -> Result<Ipv6Address, InvalidNat64Prefix> {
+        let prefixlen = nat64_prefixlen(self)?;
+        let ipv4 = u128::from(ipv4.value());
+        let embedded = if prefixlen == 96 {
+            ipv4
+        } else {
+            // Number of bits of the IPv4 address that fit between the prefix and the u octet
+            let before = U_OCTET_OFFSET - prefixlen;
+            let after = 32 - before;
+            let high = (ipv4 >> after) << U_OCTET_OFFSET;
+            let low = (ipv4 & ((1 << after) - 1)) << (SUFFIX_LEN - after);
+            high | low
+        };
+        Ok(Ipv6Address(self.network().value() | embedded))
+    }
+
+    /// Extract the IPv4 address embedded in `ipv6`, assuming `ipv6` was built from this NAT64
+    /// prefix. This is the reverse operation of [`embed_ipv4()`](#method.embed_ipv4).
+    ///
+    /// The prefix length must be 32, 40, 48, 56, 64 or 96. Note that this method does not check
+    /// that `ipv6` actually belongs to this prefix: the IPv4 address is read from the bits that
+    /// follow the prefix, as described in [RFC6052](https://tools.ietf.org/html/rfc6052#section-2.2).
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv6Address, Ipv6Network};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let prefix = Ipv6Network::from_str("2001:db8:122:300::/56").unwrap();
+    /// let ipv6 = Ipv6Address::from_str("2001:db8:122:3c0:0:221::").unwrap();
+    /// assert_eq!(
+    ///     prefix.extract_ipv4(ipv6).unwrap(),
+    ///     Ipv4Address::from_str("192.0.2.33").unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn extract_ipv4(&self, ipv6: Ipv6Address) -> Result<Ipv4Address, InvalidNat64Prefix> {
+        let prefixlen = nat64_prefixlen(self)?;
+        let ipv6 = ipv6.value();
+        let ipv4 = if prefixlen == 96 {
+            ipv6 & 0xffff_ffff
+        } else {
+            let before = U_OCTET_OFFSET - prefixlen;
+            let after = 32 - before;
+            let high = (ipv6 >> U_OCTET_OFFSET) & ((1 << before) - 1);
+            let low = (ipv6 >> (SUFFIX_LEN - after)) & ((1 << after) - 1);
+            (high << after) | low
+        };
+        Ok(Ipv4Address(ipv4 as u32))
+    }
+
+    /// Return `true` if this network is the NAT64 well-known prefix `64:ff9b::/96`. See
+    /// [RFC6052](https://tools.ietf.org/html/rfc6052#section-2.1).
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Network;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// assert!(Ipv6Network::from_str("64:ff9b::/96").unwrap().is_nat64_well_known_prefix());
+    /// assert!(!Ipv6Network::from_str("64:ff9b::/64").unwrap().is_nat64_well_known_prefix());
+    /// # }
+    /// ```
+    pub fn is_nat64_well_known_prefix(&self) -> bool {
+        self.mask() == IPV6_NAT64_WELL_KNOWN_PREFIX.mask()
+            && self.network() == IPV6_NAT64_WELL_KNOWN_PREFIX.network()
+    }
+}
+
+impl Ipv6Address {
+    /// Return `true` if this address belongs to the NAT64 well-known prefix `64:ff9b::/96`. See
+    /// [RFC6052](https://tools.ietf.org/html/rfc6052#section-2.1).
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// assert!(Ipv6Address::from_str("64:ff9b::192.0.2.33").unwrap().is_nat64_well_known());
+    /// assert!(!Ipv6Address::from_str("64:ff9b:1::192.0.2.33").unwrap().is_nat64_well_known());
+    /// # }
+    /// ```
+    pub fn is_nat64_well_known(&self) -> bool {
+        self & IPV6_NAT64_WELL_KNOWN_PREFIX.mask() == IPV6_NAT64_WELL_KNOWN_PREFIX.network()
+    }
+}
+
+/// Return the prefix length of the given network if it can be used as a NAT64 prefix.
+fn nat64_prefixlen(network: &Ipv6Network) -> Result<u32, InvalidNat64Prefix> {
+    match network.mask().prefixlen() {
+        n @ 32 | n @ 40 | n @ 48 | n @ 56 | n @ 64 | n @ 96 => Ok(n),
+        _ => Err(InvalidNat64Prefix),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    // Examples from RFC6052 section 2.4
+    const EXAMPLES: [(&str, &str); 7] = [
+        ("2001:db8::/32", "2001:db8:c000:221::"),
+        ("2001:db8:100::/40", "2001:db8:1c0:2:21::"),
+        ("2001:db8:122::/48", "2001:db8:122:c000:2:2100::"),
+        ("2001:db8:122:300::/56", "2001:db8:122:3c0:0:221::"),
+        ("2001:db8:122:344::/64", "2001:db8:122:344:c0:2:2100:0"),
+        ("2001:db8:122:344::/96", "2001:db8:122:344::192.0.2.33"),
+        ("64:ff9b::/96", "64:ff9b::192.0.2.33"),
+    ];
+
+    #[test]
+    fn test_embed_ipv4() {
+        let ipv4 = Ipv4Address::from_str("192.0.2.33").unwrap();
+        for &(prefix, expected) in &EXAMPLES {
+            let prefix = Ipv6Network::from_str(prefix).unwrap();
+            let expected = Ipv6Address::from_str(expected).unwrap();
+            assert_eq!(prefix.embed_ipv4(ipv4).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_extract_ipv4() {
+        let expected = Ipv4Address::from_str("192.0.2.33").unwrap();
+        for &(prefix, ipv6) in &EXAMPLES {
+            let prefix = Ipv6Network::from_str(prefix).unwrap();
+            let ipv6 = Ipv6Address::from_str(ipv6).unwrap();
+            assert_eq!(prefix.extract_ipv4(ipv6).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let ipv4 = Ipv4Address(0xffff_ffff);
+        for &(prefix, _) in &EXAMPLES {
+            let prefix = Ipv6Network::from_str(prefix).unwrap();
+            let ipv6 = prefix.embed_ipv4(ipv4).unwrap();
+            // the u octet is always zero
+            assert_eq!(ipv6.value() & 0x0000_0000_0000_0000_ff00_0000_0000_0000, 0);
+            assert_eq!(prefix.extract_ipv4(ipv6).unwrap(), ipv4);
+        }
+    }
+
+    #[test]
+    fn test_invalid_prefix() {
+        let ipv4 = Ipv4Address::from_str("192.0.2.33").unwrap();
+        for prefix in &["2001:db8::/0", "2001:db8::/33", "2001:db8::/72", "2001:db8::/128"] {
+            let prefix = Ipv6Network::from_str(prefix).unwrap();
+            assert!(prefix.embed_ipv4(ipv4).is_err());
+            assert!(prefix.extract_ipv4(IPV6_NAT64_WELL_KNOWN_PREFIX.network()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_well_known_prefix() {
+        assert!(IPV6_NAT64_WELL_KNOWN_PREFIX.is_nat64_well_known_prefix());
+        assert!(Ipv6Address::from_str("64:ff9b::").unwrap().is_nat64_well_known());
+        assert!(Ipv6Address::from_str("64:ff9b::ffff:ffff").unwrap().is_nat64_well_known());
+        assert!(!Ipv6Address::from_str("64:ff9b::1:0:0").unwrap().is_nat64_well_known());
+        assert!(!Ipv6Address::from_str("::ffff:1.2.3.4").unwrap().is_nat64_well_known());
+    }
+}
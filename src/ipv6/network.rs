@@ -1,10 +1,13 @@
-use std::fmt;
-use std::str::FromStr;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
 
-use {Ipv6Address, Ipv6Mask, ParsingFailed};
+use {Ipv6Address, Ipv6Mask, ParseError, ParsingFailed};
+use ParseErrorReason::*;
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
-pub struct Ipv6Network(Ipv6Address, Ipv6Mask);
+pub struct Ipv6Network(pub(crate) Ipv6Address, pub(crate) Ipv6Mask);
 
 impl Ipv6Network {
     /// Create a new network address from the given address and mask.
@@ -48,20 +51,32 @@ impl Ipv6Network {
         &mut self.1
     }
 
+    /// Return a human readable representation of the network. The mask is represented by its
+    /// prefix length.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Network;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let net = Ipv6Network::from_str("2001:db8::1/ffff:ffff::").unwrap();
+    /// assert_eq!(net.to_string(), "2001:db8::1/32");
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
     pub fn to_string(&self) -> String {
-        unimplemented!()
+        format!("{}/{}", self.0, self.1.prefixlen())
     }
 }
 
 impl fmt::Display for Ipv6Network {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        unimplemented!()
+        write!(f, "{}/{}", self.0, self.1.prefixlen())
     }
 }
 
 impl fmt::Debug for Ipv6Network {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        unimplemented!()
+        write!(f, "Ipv6Network({}/{})", self.0, self.1.prefixlen())
     }
 }
 
@@ -69,29 +84,116 @@ impl FromStr for Ipv6Network {
     type Err = ParsingFailed;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split = s.split('/');
-        let ip = Ipv6Address::from_str(split.next().ok_or_else(|| ParsingFailed(s.into()))?)?;
+        Ipv6Network::parse(s.as_bytes()).map_err(|e| ParsingFailed::new(s, e))
+    }
+}
+
+impl Ipv6Network {
+    /// Parse a network from ASCII bytes. This is the same as `from_str()`, but the input does not
+    /// need to be valid UTF-8.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Network;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// assert_eq!(
+    ///     Ipv6Network::from_ascii(b"2001:db8::/32").unwrap(),
+    ///     Ipv6Network::from_str("2001:db8::/32").unwrap()
+    /// );
+    /// assert!(Ipv6Network::from_ascii(b"2001:db8::/129").is_err());
+    /// # }
+    /// ```
+    pub fn from_ascii(bytes: &[u8]) -> Result<Self, ParsingFailed> {
+        Ipv6Network::parse(bytes).map_err(|e| ParsingFailed::from_bytes(bytes, e))
+    }
 
-        let mask_str = split.next().ok_or_else(|| ParsingFailed(s.into()))?;
+    /// Parse an IPv6 network at compile time. This is meant to be used in constants: an invalid
+    /// string is then a build error. The [`ipv6_network!`](macro.ipv6_network.html) macro is a shortcut for
+    /// this.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the string is not a valid IPv6 network.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Network;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// const VALUE: Ipv6Network = Ipv6Network::parse_const("2001:db8::/32");
+    /// assert_eq!(VALUE, Ipv6Network::from_str("2001:db8::/32").unwrap());
+    /// # }
+    /// ```
+    ///
+    /// ```rust,compile_fail
+    /// # use ipaddr::Ipv6Network;
+    /// const VALUE: Ipv6Network = Ipv6Network::parse_const("2001:db8::/129");
+    /// ```
+    pub const fn parse_const(s: &str) -> Self {
+        match Ipv6Network::parse(s.as_bytes()) {
+            Ok(value) => value,
+            Err(e) => panic!("{}", e.reason().description()),
+        }
+    }
+
+    // This is a `const fn`, so that networks can be parsed at compile time. This is why it uses
+    // `while` loops and `match` instead of iterators and `?`.
+    const fn parse(bytes: &[u8]) -> Result<Self, ParseError> {
+        let mut slash = 0;
+        while slash < bytes.len() && bytes[slash] != b'/' {
+            slash += 1;
+        }
+        if slash == bytes.len() {
+            return Err(ParseError::new(bytes.len(), UnexpectedEnd));
+        }
+        let (ip_bytes, mask_bytes) = bytes.split_at(slash);
+        let ip = match Ipv6Address::parse(ip_bytes) {
+            Ok(ip) => Ipv6Address(ip),
+            Err(e) => return Err(e),
+        };
+
+        let mask_bytes = mask_bytes.split_at(1).1;
+        if mask_bytes.is_empty() {
+            return Err(ParseError::new(bytes.len(), UnexpectedEnd));
+        }
 
         // The most common is to represent the mask with as a prefix length, so we try to parse the
         // string as an integer first.
-        if let Ok(prefix_len) = mask_str.parse::<u8>() {
-            let mask = Ipv6Mask::from_prefixlen(prefix_len).map_err(|_| ParsingFailed(s.into()))?;
-            return Ok(Ipv6Network(ip, mask));
+        if let Some(prefix_len) = parse_prefixlen(mask_bytes) {
+            if prefix_len <= 0xff {
+                if let Ok(mask) = Ipv6Mask::from_prefixlen(prefix_len as u8) {
+                    return Ok(Ipv6Network(ip, mask));
+                }
+            }
+            return Err(ParseError::new(slash + 1, InvalidPrefixLength));
         }
 
         // If that didn't work, may it has been specified as an IPv6
-        let mask = Ipv6Mask::from_str(mask_str)?;
+        match Ipv6Mask::parse(mask_bytes) {
+            Ok(mask) => Ok(Ipv6Network(ip, mask)),
+            Err(e) => Err(e.shift(slash + 1)),
+        }
+    }
+}
 
-        Ok(Ipv6Network(ip, mask))
+/// Parse a prefix length. Return `None` if the bytes are not all decimal digits. Values that do
+/// not fit in a `u16` are replaced by `u16::MAX`.
+const fn parse_prefixlen(bytes: &[u8]) -> Option<u16> {
+    let mut value: u16 = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            return None;
+        }
+        value = value.saturating_mul(10).saturating_add((bytes[i] - b'0') as u16);
+        i += 1;
     }
+    Some(value)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::convert::TryFrom;
+    use core::convert::TryFrom;
 
     const IP: u128 = 0xfe80_0000_0000_0000_aef4_1242_24e6_0c81;
 
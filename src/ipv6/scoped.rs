@@ -0,0 +1,435 @@
+//! Scoped IPv6 addresses, i.e. IPv6 addresses with a zone identifier such as `fe80::1%eth0`. See
+//! [RFC4007](https://tools.ietf.org/html/rfc4007#section-11) and
+//! [RFC6874](https://tools.ietf.org/html/rfc6874).
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use ParseErrorReason::*;
+use {Ipv6Address, Ipv6Formatter, ParseError, ParsingFailed};
+
+/// The zone identifier of a scoped IPv6 address. Zones are usually network interfaces, that can
+/// be identified either by their name or by their index.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Ipv6Zone {
+    /// Numeric zone identifier, such as `3` in `fe80::1%3`
+    Index(u32),
+    /// Textual zone identifier, such as `eth0` in `fe80::1%eth0`. Zone identifiers that only
+    /// contain digits are parsed as an [`Index`](#variant.Index) when they fit in a `u32`, so a
+    /// name like `3` is written `%3` but read back as `Index(3)`.
+    Name(String),
+}
+
+impl Ipv6Zone {
+    /// Return the numeric index of this zone. If the zone is identified by its name, the
+    /// `resolver` is used to find the corresponding index.
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv6Zone, ZoneResolver};
+    /// # fn main() {
+    /// struct Resolver;
+    ///
+    /// impl ZoneResolver for Resolver {
+    ///     fn index(&self, name: &str) -> Option<u32> {
+    ///         if name == "eth0" { Some(2) } else { None }
+    ///     }
+    ///
+    ///     fn name(&self, index: u32) -> Option<String> {
+    ///         if index == 2 { Some("eth0".into()) } else { None }
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(Ipv6Zone::Index(3).to_index(&Resolver), Some(3));
+    /// assert_eq!(Ipv6Zone::Name("eth0".into()).to_index(&Resolver), Some(2));
+    /// assert_eq!(Ipv6Zone::Name("eth1".into()).to_index(&Resolver), None);
+    /// # }
+    /// ```
+    pub fn to_index<R: ZoneResolver + ?Sized>(&self, resolver: &R) -> Option<u32> {
+        match *self {
+            Ipv6Zone::Index(index) => Some(index),
+            Ipv6Zone::Name(ref name) => resolver.index(name),
+        }
+    }
+
+    /// Return the name of this zone. If the zone is identified by its index, the `resolver` is
+    /// used to find the corresponding name.
+    pub fn to_name<R: ZoneResolver + ?Sized>(&self, resolver: &R) -> Option<String> {
+        match *self {
+            Ipv6Zone::Index(index) => resolver.name(index),
+            Ipv6Zone::Name(ref name) => Some(name.clone()),
+        }
+    }
+
+    /// Parse a zone identifier. Identifiers made only of decimal digits are zone indices, and
+    /// anything else is a zone name.
+    fn parse(s: &str) -> Option<Ipv6Zone> {
+        if s.is_empty() {
+            return None;
+        }
+        if s.bytes().all(|b| b.is_ascii_digit()) {
+            if let Ok(index) = s.parse::<u32>() {
+                return Some(Ipv6Zone::Index(index));
+            }
+        }
+        Some(Ipv6Zone::Name(s.into()))
+    }
+
+    /// Write the zone identifier, percent-encoding the characters that are not "unreserved" in
+    /// URIs (see [RFC3986](https://tools.ietf.org/html/rfc3986#section-2.3)).
+    pub(crate) fn write_url_encoded<W: fmt::Write>(&self, writer: &mut W) -> fmt::Result {
+        match *self {
+            Ipv6Zone::Index(index) => write!(writer, "{}", index),
+            Ipv6Zone::Name(ref name) => {
+                for b in name.bytes() {
+                    if is_unreserved(b) {
+                        writer.write_char(b as char)?;
+                    } else {
+                        write!(writer, "%{:02X}", b)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Ipv6Zone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Ipv6Zone::Index(index) => write!(f, "{}", index),
+            Ipv6Zone::Name(ref name) => f.write_str(name),
+        }
+    }
+}
+
+/// Map zone names to zone indices and vice versa. On Linux,
+/// [`SysfsZoneResolver`](struct.SysfsZoneResolver.html) resolves network interface names.
+pub trait ZoneResolver {
+    /// Return the index of the zone with the given name, or `None` if there is no such zone.
+    fn index(&self, name: &str) -> Option<u32>;
+
+    /// Return the name of the zone with the given index, or `None` if there is no such zone.
+    fn name(&self, index: u32) -> Option<String>;
+}
+
+/// A [`ZoneResolver`](trait.ZoneResolver.html) that resolves network interfaces names and indices
+/// by reading `/sys/class/net/<interface>/ifindex`.
+#[cfg(all(target_os = "linux", feature = "std"))]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SysfsZoneResolver;
+
+#[cfg(all(target_os = "linux", feature = "std"))]
+impl SysfsZoneResolver {
+    const SYSFS_NET: &'static str = "/sys/class/net";
+
+    fn read_index(name: &str) -> Option<u32> {
+        // Do not let a zone name escape /sys/class/net
+        if name.is_empty() || name == "." || name == ".." || name.contains('/') {
+            return None;
+        }
+        let path = format!("{}/{}/ifindex", Self::SYSFS_NET, name);
+        ::std::fs::read_to_string(path)
+            .ok()
+            .and_then(|index| index.trim().parse().ok())
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "std"))]
+impl ZoneResolver for SysfsZoneResolver {
+    fn index(&self, name: &str) -> Option<u32> {
+        Self::read_index(name)
+    }
+
+    fn name(&self, index: u32) -> Option<String> {
+        ::std::fs::read_dir(Self::SYSFS_NET)
+            .ok()?
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .find(|name| Self::read_index(name) == Some(index))
+    }
+}
+
+/// An IPv6 address with a zone identifier, such as `fe80::1%eth0` or `fe80::1%3`. See
+/// [RFC4007](https://tools.ietf.org/html/rfc4007#section-11).
+///
+/// ```rust
+/// # use ipaddr::{Ipv6Address, Ipv6Zone, ScopedIpv6Address};
+/// # use std::str::FromStr;
+/// # fn main() {
+/// let ip = ScopedIpv6Address::from_str("fe80::1%eth0").unwrap();
+/// assert_eq!(ip.address(), Ipv6Address::from_str("fe80::1").unwrap());
+/// assert_eq!(ip.zone(), &Ipv6Zone::Name("eth0".into()));
+/// assert_eq!(ip.to_string(), "fe80::1%eth0");
+///
+/// let ip = ScopedIpv6Address::from_str("fe80::1%3").unwrap();
+/// assert_eq!(ip.zone(), &Ipv6Zone::Index(3));
+/// # }
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct ScopedIpv6Address {
+    address: Ipv6Address,
+    zone: Ipv6Zone,
+}
+
+impl ScopedIpv6Address {
+    /// Create a new scoped address from the given address and zone.
+    pub fn new(address: Ipv6Address, zone: Ipv6Zone) -> Self {
+        ScopedIpv6Address { address, zone }
+    }
+
+    /// Return the address, without the zone identifier.
+    pub fn address(&self) -> Ipv6Address {
+        self.address
+    }
+
+    /// Return the zone identifier.
+    pub fn zone(&self) -> &Ipv6Zone {
+        &self.zone
+    }
+
+    /// Parse a scoped address in the format described in
+    /// [RFC6874](https://tools.ietf.org/html/rfc6874#section-2) for URIs: the `%` delimiter is
+    /// encoded as `%25`, and the zone identifier may contain percent-encoded characters. The
+    /// address may be enclosed in brackets.
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv6Zone, ScopedIpv6Address};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = ScopedIpv6Address::from_uri_str("[fe80::1%25eth0]").unwrap();
+    /// assert_eq!(ip, ScopedIpv6Address::from_str("fe80::1%eth0").unwrap());
+    ///
+    /// let ip = ScopedIpv6Address::from_uri_str("fe80::1%25en%2F1").unwrap();
+    /// assert_eq!(ip.zone(), &Ipv6Zone::Name("en/1".into()));
+    ///
+    /// // the % delimiter must be encoded
+    /// assert!(ScopedIpv6Address::from_uri_str("fe80::1%eth0").is_err());
+    /// # }
+    /// ```
+    pub fn from_uri_str(s: &str) -> Result<Self, ParsingFailed> {
+        let err = |offset, reason| ParsingFailed::new(s, ParseError::new(offset, reason));
+        let (start, unbracketed) = if s.starts_with('[') && s.ends_with(']') && s.len() >= 2 {
+            (1, &s[1..s.len() - 1])
+        } else {
+            (0, s)
+        };
+        let delimiter = unbracketed
+            .find("%25")
+            .ok_or_else(|| err(start + unbracketed.len(), UnexpectedEnd))?;
+        let address = Ipv6Address::parse(&unbracketed.as_bytes()[..delimiter])
+            .map_err(|e| ParsingFailed::new(s, e.shift(start)))?;
+        let zone_start = start + delimiter + 3;
+        let zone = percent_decode(&unbracketed[delimiter + 3..])
+            .ok_or_else(|| err(zone_start, InvalidCharacter))?;
+        let zone = Ipv6Zone::parse(&zone).ok_or_else(|| err(zone_start, UnexpectedEnd))?;
+        let address = Ipv6Address(address);
+        Ok(ScopedIpv6Address { address, zone })
+    }
+
+    /// Create a formatter to stringify this scoped address. The zone identifier is written after
+    /// the address. See [`Ipv6Formatter<'a, W>`](struct.Ipv6Formatter.html) for more details.
+    pub fn formatter<'a, W: fmt::Write>(&'a self, writer: &'a mut W) -> Ipv6Formatter<'a, W> {
+        self.address.formatter(writer).with_zone(&self.zone)
+    }
+}
+
+impl fmt::Display for ScopedIpv6Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.formatter(f).write()
+    }
+}
+
+impl fmt::Debug for ScopedIpv6Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ScopedIpv6Address(")?;
+        self.formatter(f).write()?;
+        f.write_str(")")
+    }
+}
+
+impl FromStr for ScopedIpv6Address {
+    type Err = ParsingFailed;
+
+    /// Parse a scoped address in the textual format described in
+    /// [RFC4007](https://tools.ietf.org/html/rfc4007#section-11.2): `<address>%<zone_id>`. Use
+    /// [`from_uri_str()`](#method.from_uri_str) to parse the format used in URIs.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = |offset, reason| ParsingFailed::new(s, ParseError::new(offset, reason));
+        let delimiter = s.find('%').ok_or_else(|| err(s.len(), UnexpectedEnd))?;
+        let address = Ipv6Address::parse(&s.as_bytes()[..delimiter])
+            .map(Ipv6Address)
+            .map_err(|e| ParsingFailed::new(s, e))?;
+        let zone =
+            Ipv6Zone::parse(&s[delimiter + 1..]).ok_or_else(|| err(s.len(), UnexpectedEnd))?;
+        Ok(ScopedIpv6Address { address, zone })
+    }
+}
+
+/// Return `true` if the given character is an "unreserved" URI character, see
+/// [RFC3986](https://tools.ietf.org/html/rfc3986#section-2.3).
+fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'-' || b == b'.' || b == b'_' || b == b'~'
+}
+
+/// Decode a percent-encoded string. Return `None` if the string contains an invalid
+/// percent-encoded sequence, or if the decoded bytes are not valid UTF-8.
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes.get(i + 1..i + 3)?;
+            // `from_str_radix()` accepts a sign, like in `%+1`
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return None;
+            }
+            let hex = ::core::str::from_utf8(hex).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    const LINK_LOCAL: Ipv6Address = Ipv6Address(0xfe80_0000_0000_0000_0000_0000_0000_0001);
+
+    fn scoped(zone: Ipv6Zone) -> ScopedIpv6Address {
+        ScopedIpv6Address::new(LINK_LOCAL, zone)
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            ScopedIpv6Address::from_str("fe80::1%eth0").unwrap(),
+            scoped(Ipv6Zone::Name("eth0".into()))
+        );
+        assert_eq!(
+            ScopedIpv6Address::from_str("fe80::1%3").unwrap(),
+            scoped(Ipv6Zone::Index(3))
+        );
+        assert_eq!(
+            ScopedIpv6Address::from_str("fe80::1%25eth0").unwrap(),
+            scoped(Ipv6Zone::Name("25eth0".into()))
+        );
+        // too large to be an index
+        assert_eq!(
+            ScopedIpv6Address::from_str("fe80::1%4294967296").unwrap(),
+            scoped(Ipv6Zone::Name("4294967296".into()))
+        );
+
+        assert!(ScopedIpv6Address::from_str("fe80::1").is_err());
+        assert!(ScopedIpv6Address::from_str("fe80::1%").is_err());
+        assert!(ScopedIpv6Address::from_str("%eth0").is_err());
+        assert!(ScopedIpv6Address::from_str("fe80::1::%eth0").is_err());
+    }
+
+    #[test]
+    fn test_from_uri_str() {
+        assert_eq!(
+            ScopedIpv6Address::from_uri_str("fe80::1%25eth0").unwrap(),
+            scoped(Ipv6Zone::Name("eth0".into()))
+        );
+        assert_eq!(
+            ScopedIpv6Address::from_uri_str("[fe80::1%253]").unwrap(),
+            scoped(Ipv6Zone::Index(3))
+        );
+        assert_eq!(
+            ScopedIpv6Address::from_uri_str("fe80::1%25%65th%300").unwrap(),
+            scoped(Ipv6Zone::Name("eth00".into()))
+        );
+
+        assert!(ScopedIpv6Address::from_uri_str("fe80::1%eth0").is_err());
+        assert!(ScopedIpv6Address::from_uri_str("fe80::1%25").is_err());
+        assert!(ScopedIpv6Address::from_uri_str("fe80::1%25eth%2").is_err());
+        assert!(ScopedIpv6Address::from_uri_str("fe80::1%25eth%zz").is_err());
+        assert!(ScopedIpv6Address::from_uri_str("fe80::1%25eth%ff").is_err());
+        assert!(ScopedIpv6Address::from_uri_str("fe80::1%25%+1").is_err());
+        assert!(ScopedIpv6Address::from_uri_str("fe80::1%25eth%-1").is_err());
+        assert!(ScopedIpv6Address::from_uri_str("[fe80::1%25eth0").is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        let ip = scoped(Ipv6Zone::Name("en/1".into()));
+        assert_eq!(ip.to_string(), "fe80::1%en/1");
+        assert_eq!(format!("{}", ip), "fe80::1%en/1");
+        assert_eq!(format!("{:?}", ip), "ScopedIpv6Address(fe80::1%en/1)");
+
+        let mut s = String::with_capacity(50);
+        ip.formatter(&mut s)
+            .expanded()
+            .url_encoded_zone(true)
+            .write()
+            .unwrap();
+        assert_eq!(s, "fe80:0000:0000:0000:0000:0000:0000:0001%25en%2F1");
+        assert_eq!(ScopedIpv6Address::from_uri_str(&s).unwrap(), ip);
+
+        // names that only contain digits are read back as indices
+        let ip = scoped(Ipv6Zone::Name("3".into()));
+        assert_eq!(ip.to_string(), "fe80::1%3");
+        assert_eq!(
+            ScopedIpv6Address::from_str(&ip.to_string()).unwrap(),
+            scoped(Ipv6Zone::Index(3))
+        );
+
+        let ip = scoped(Ipv6Zone::Index(42));
+        assert_eq!(ip.to_string(), "fe80::1%42");
+        assert_eq!(ScopedIpv6Address::from_str(&ip.to_string()).unwrap(), ip);
+    }
+
+    struct Resolver;
+
+    impl ZoneResolver for Resolver {
+        fn index(&self, name: &str) -> Option<u32> {
+            match name {
+                "lo" => Some(1),
+                "eth0" => Some(2),
+                _ => None,
+            }
+        }
+
+        fn name(&self, index: u32) -> Option<String> {
+            match index {
+                1 => Some("lo".into()),
+                2 => Some("eth0".into()),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_resolver() {
+        let zone = Ipv6Zone::Name("eth0".into());
+        assert_eq!(zone.to_index(&Resolver), Some(2));
+        assert_eq!(zone.to_name(&Resolver), Some("eth0".into()));
+
+        let zone = Ipv6Zone::Index(1);
+        assert_eq!(zone.to_index(&Resolver), Some(1));
+        assert_eq!(zone.to_name(&Resolver), Some("lo".into()));
+
+        assert_eq!(Ipv6Zone::Name("eth1".into()).to_index(&Resolver), None);
+        assert_eq!(Ipv6Zone::Index(3).to_name(&Resolver), None);
+    }
+
+    #[cfg(all(target_os = "linux", feature = "std"))]
+    #[test]
+    fn test_sysfs_resolver() {
+        let resolver = SysfsZoneResolver;
+        assert_eq!(resolver.index(".."), None);
+        assert_eq!(resolver.index("lo/../lo"), None);
+        assert_eq!(resolver.index("this interface does not exist"), None);
+        // The loopback interface may not be visible in some sandboxed environments
+        if let Some(index) = resolver.index("lo") {
+            assert_eq!(resolver.name(index), Some("lo".into()));
+        }
+    }
+}
@@ -0,0 +1,325 @@
+//! IPv6 addresses used by IPv4 tunneling mechanisms: 6to4
+//! ([RFC3056](https://tools.ietf.org/html/rfc3056)), Teredo
+//! ([RFC4380](https://tools.ietf.org/html/rfc4380)) and ISATAP
+//! ([RFC5214](https://tools.ietf.org/html/rfc5214)).
+
+use {Ipv4Address, Ipv6Address, IPV6_6TO4_PREFIX, IPV6_TEREDO_PREFIX};
+
+/// Bits 32 to 63 of an ISATAP interface identifier, with the "u" bit cleared.
+const ISATAP_IID: u128 = 0x0000_5efe;
+
+/// The "u" bit of a modified EUI-64 interface identifier. It is set in ISATAP interface
+/// identifiers that embed a globally unique IPv4 address.
+const ISATAP_U_BIT: u128 = 0x0200_0000;
+
+/// The information embedded in a Teredo address. See
+/// [RFC4380](https://tools.ietf.org/html/rfc4380#section-4). The format of a Teredo address is
+/// as follows:
+///
+/// ```no_rust
+/// |   32 bits  |   32 bits   |  16 bits |  16 bits  |      32 bits      |
+/// +------------+-------------+----------+-----------+-------------------+
+/// |  2001:0000 | server IPv4 |   flags  | obfs port | obfs client IPv4  |
+/// +------------+-------------+----------+-----------+-------------------+
+/// ```
+///
+/// The client port and the client IPv4 address are "obfuscated": all their bits are inverted.
+/// The fields of this structure hold the actual values, not the obfuscated ones.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Teredo {
+    /// IPv4 address of the Teredo server
+    pub server: Ipv4Address,
+    /// Teredo flags. The most significant bit is the "cone" bit.
+    pub flags: u16,
+    /// External UDP port of the Teredo client
+    pub client_port: u16,
+    /// External IPv4 address of the Teredo client
+    pub client: Ipv4Address,
+}
+
+impl Teredo {
+    /// Return `true` if the "cone" flag is set, i.e. if the client is behind a cone NAT.
+    pub fn is_cone(&self) -> bool {
+        self.flags & 0x8000 != 0
+    }
+}
+
+impl Ipv6Address {
+    /// Return `true` if this address is a 6to4 address, i.e. if it belongs to `2002::/16`. See
+    /// [RFC3056](https://tools.ietf.org/html/rfc3056#section-2).
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// assert!(Ipv6Address::from_str("2002:c000:22d::1").unwrap().is_6to4());
+    /// assert!(!Ipv6Address::from_str("2001:db8::1").unwrap().is_6to4());
+    /// # }
+    /// ```
+    pub fn is_6to4(&self) -> bool {
+        self & IPV6_6TO4_PREFIX.mask() == IPV6_6TO4_PREFIX.network()
+    }
+
+    /// Return the IPv4 address embedded in this 6to4 address, or `None` if this is not a 6to4
+    /// address. The format of a 6to4 address is as follows:
+    ///
+    /// ```no_rust
+    /// | 16 bits |   32 bits   | 16 bits |       64 bits        |
+    /// +---------+-------------+---------+----------------------+
+    /// |  2002   |    IPv4     | SLA ID  |    interface ID      |
+    /// +---------+-------------+---------+----------------------+
+    /// ```
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv6Address};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = Ipv6Address::from_str("2002:c000:22d::1").unwrap();
+    /// assert_eq!(ip.to_6to4_ipv4(), Some(Ipv4Address::from_str("192.0.2.45").unwrap()));
+    /// # }
+    /// ```
+    pub fn to_6to4_ipv4(&self) -> Option<Ipv4Address> {
+        if self.is_6to4() {
+            Some(Ipv4Address((self.0 >> 80) as u32))
+        } else {
+            None
+        }
+    }
+
+    /// Create a 6to4 address from an IPv4 address, a subnet ID (the "SLA ID") and an interface
+    /// ID.
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv6Address};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ipv4 = Ipv4Address::from_str("192.0.2.45").unwrap();
+    /// assert_eq!(
+    ///     Ipv6Address::from_6to4(ipv4, 0xa, 1),
+    ///     Ipv6Address::from_str("2002:c000:22d:a::1").unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn from_6to4(ipv4: Ipv4Address, subnet_id: u16, interface_id: u64) -> Ipv6Address {
+        Ipv6Address(
+            IPV6_6TO4_PREFIX.network().0
+                | (u128::from(ipv4.0) << 80)
+                | (u128::from(subnet_id) << 64)
+                | u128::from(interface_id),
+        )
+    }
+
+    /// Return `true` if this address is a Teredo address, i.e. if it belongs to `2001::/32`.
+    /// See [RFC4380](https://tools.ietf.org/html/rfc4380#section-2.6).
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// assert!(Ipv6Address::from_str("2001:0:4136:e378:8000:63bf:3fff:fdd2").unwrap().is_teredo());
+    /// assert!(!Ipv6Address::from_str("2001:db8::1").unwrap().is_teredo());
+    /// # }
+    /// ```
+    pub fn is_teredo(&self) -> bool {
+        self & IPV6_TEREDO_PREFIX.mask() == IPV6_TEREDO_PREFIX.network()
+    }
+
+    /// Decode this Teredo address, or return `None` if this is not a Teredo address. See
+    /// [`Teredo`](struct.Teredo.html) for the format of Teredo addresses.
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv6Address};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = Ipv6Address::from_str("2001:0:4136:e378:8000:63bf:3fff:fdd2").unwrap();
+    /// let teredo = ip.to_teredo().unwrap();
+    /// assert_eq!(teredo.server, Ipv4Address::from_str("65.54.227.120").unwrap());
+    /// assert_eq!(teredo.client, Ipv4Address::from_str("192.0.2.45").unwrap());
+    /// assert_eq!(teredo.client_port, 40000);
+    /// assert!(teredo.is_cone());
+    /// # }
+    /// ```
+    pub fn to_teredo(&self) -> Option<Teredo> {
+        if !self.is_teredo() {
+            return None;
+        }
+        Some(Teredo {
+            server: Ipv4Address((self.0 >> 64) as u32),
+            flags: (self.0 >> 48) as u16,
+            client_port: !(self.0 >> 32) as u16,
+            client: Ipv4Address(!self.0 as u32),
+        })
+    }
+
+    /// Create a Teredo address. This is the reverse operation of
+    /// [`to_teredo()`](#method.to_teredo).
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv6Address, Teredo};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let teredo = Teredo {
+    ///     server: Ipv4Address::from_str("65.54.227.120").unwrap(),
+    ///     flags: 0x8000,
+    ///     client_port: 40000,
+    ///     client: Ipv4Address::from_str("192.0.2.45").unwrap(),
+    /// };
+    /// assert_eq!(
+    ///     Ipv6Address::from_teredo(teredo),
+    ///     Ipv6Address::from_str("2001:0:4136:e378:8000:63bf:3fff:fdd2").unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn from_teredo(teredo: Teredo) -> Ipv6Address {
+        Ipv6Address(
+            IPV6_TEREDO_PREFIX.network().0
+                | (u128::from(teredo.server.0) << 64)
+                | (u128::from(teredo.flags) << 48)
+                | (u128::from(!teredo.client_port) << 32)
+                | u128::from(!teredo.client.0),
+        )
+    }
+
+    /// Return `true` if this address has an ISATAP interface identifier. See
+    /// [RFC5214](https://tools.ietf.org/html/rfc5214#section-6.1).
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// assert!(Ipv6Address::from_str("fe80::5efe:192.0.2.143").unwrap().is_isatap());
+    /// assert!(Ipv6Address::from_str("2001:db8::200:5efe:192.0.2.143").unwrap().is_isatap());
+    /// assert!(!Ipv6Address::from_str("fe80::1").unwrap().is_isatap());
+    /// # }
+    /// ```
+    pub fn is_isatap(&self) -> bool {
+        (self.0 >> 32) & !ISATAP_U_BIT & 0xffff_ffff == ISATAP_IID
+    }
+
+    /// Return the IPv4 address embedded in the interface identifier of this ISATAP address, or
+    /// `None` if this is not an ISATAP address. The format of an ISATAP interface identifier is as
+    /// follows:
+    ///
+    /// ```no_rust
+    /// |    64 bits    |   24 bits   | 8 bits |      32 bits        |
+    /// +---------------+-------------+--------+---------------------+
+    /// |    prefix     | 0000:00 (*) |   FE   |    IPv4 address     |
+    /// +---------------+-------------+--------+---------------------+
+    /// ```
+    ///
+    /// (*) the "u" bit is set to `1` (`0200:5efe`) if the IPv4 address is globally unique.
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv6Address};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = Ipv6Address::from_str("fe80::5efe:c000:28f").unwrap();
+    /// assert_eq!(ip.to_isatap_ipv4(), Some(Ipv4Address::from_str("192.0.2.143").unwrap()));
+    /// # }
+    /// ```
+    pub fn to_isatap_ipv4(&self) -> Option<Ipv4Address> {
+        if self.is_isatap() {
+            Some(Ipv4Address(self.0 as u32))
+        } else {
+            None
+        }
+    }
+
+    /// Create an ISATAP address from a 64 bits prefix and an IPv4 address. Only the 64 most
+    /// significant bits of `prefix` are used. `global` indicates whether the IPv4 address is
+    /// globally unique, in which case the "u" bit of the interface identifier is set.
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv6Address};
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let prefix = Ipv6Address::from_str("2001:db8::").unwrap();
+    /// let ipv4 = Ipv4Address::from_str("192.0.2.143").unwrap();
+    /// assert_eq!(
+    ///     Ipv6Address::from_isatap(prefix, ipv4, true),
+    ///     Ipv6Address::from_str("2001:db8::200:5efe:c000:28f").unwrap()
+    /// );
+    /// assert_eq!(
+    ///     Ipv6Address::from_isatap(prefix, ipv4, false),
+    ///     Ipv6Address::from_str("2001:db8::5efe:c000:28f").unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn from_isatap(prefix: Ipv6Address, ipv4: Ipv4Address, global: bool) -> Ipv6Address {
+        let mut iid = ISATAP_IID;
+        if global {
+            iid |= ISATAP_U_BIT;
+        }
+        Ipv6Address(
+            (prefix.0 & 0xffff_ffff_ffff_ffff_0000_0000_0000_0000)
+                | (iid << 32)
+                | u128::from(ipv4.0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    #[test]
+    fn test_6to4() {
+        let ip = Ipv6Address::from_str("2002:c000:22d:a:1:2:3:4").unwrap();
+        let ipv4 = Ipv4Address::from_str("192.0.2.45").unwrap();
+        assert_eq!(ip.to_6to4_ipv4(), Some(ipv4));
+        assert_eq!(Ipv6Address::from_6to4(ipv4, 0xa, 0x0001_0002_0003_0004), ip);
+
+        assert_eq!(
+            Ipv6Address::from_str("2003:c000:22d::")
+                .unwrap()
+                .to_6to4_ipv4(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_teredo() {
+        // Example from RFC4380 section 4
+        let ip = Ipv6Address::from_str("2001:0:4136:e378:8000:63bf:3fff:fdd2").unwrap();
+        let expected = Teredo {
+            server: Ipv4Address::from_str("65.54.227.120").unwrap(),
+            flags: 0x8000,
+            client_port: 40000,
+            client: Ipv4Address::from_str("192.0.2.45").unwrap(),
+        };
+        assert_eq!(ip.to_teredo(), Some(expected));
+        assert_eq!(Ipv6Address::from_teredo(expected), ip);
+
+        let ip = Ipv6Address::from_str("2001:0:4136:e378::ffff:ffff").unwrap();
+        let teredo = ip.to_teredo().unwrap();
+        assert!(!teredo.is_cone());
+        assert_eq!(teredo.client_port, 0xffff);
+        assert_eq!(teredo.client, Ipv4Address(0));
+
+        assert_eq!(Ipv6Address::from_str("2001:1::").unwrap().to_teredo(), None);
+    }
+
+    #[test]
+    fn test_isatap() {
+        let ipv4 = Ipv4Address::from_str("192.0.2.143").unwrap();
+        for s in &["fe80::5efe:c000:28f", "fe80::200:5efe:c000:28f"] {
+            let ip = Ipv6Address::from_str(s).unwrap();
+            assert!(ip.is_isatap());
+            assert_eq!(ip.to_isatap_ipv4(), Some(ipv4));
+        }
+
+        let prefix = Ipv6Address::from_str("fe80::ffff:ffff:ffff:ffff").unwrap();
+        assert_eq!(
+            Ipv6Address::from_isatap(prefix, ipv4, false),
+            Ipv6Address::from_str("fe80::5efe:c000:28f").unwrap()
+        );
+
+        assert!(!Ipv6Address::from_str("fe80::100:5efe:c000:28f")
+            .unwrap()
+            .is_isatap());
+        assert!(!Ipv6Address::from_str("fe80::5eff:c000:28f")
+            .unwrap()
+            .is_isatap());
+    }
+}
@@ -4,8 +4,32 @@
 #![cfg_attr(feature = "cargo-clippy", allow(module_inception))]
 #![feature(try_from)]
 #![feature(range_contains)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "std")]
 extern crate core;
+#[cfg(feature = "alloc")]
+#[macro_use]
+extern crate alloc;
+#[cfg(feature = "arrow")]
+extern crate arrow_array;
+#[cfg(feature = "arrow")]
+extern crate arrow_buffer;
+#[cfg(feature = "bytes")]
+extern crate bytes;
+#[cfg(feature = "proptest")]
+extern crate proptest;
+#[cfg(feature = "quickcheck")]
+extern crate quickcheck;
+#[cfg(any(test, feature = "rand"))]
+extern crate rand;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_test;
+
+#[macro_use]
+mod macros;
 
 mod errors;
 pub use self::errors::*;
@@ -14,3 +38,35 @@ mod ipv4;
 pub use self::ipv4::*;
 mod ipv6;
 pub use self::ipv6::*;
+mod ip;
+pub use self::ip::*;
+mod parse_options;
+pub use self::parse_options::*;
+#[cfg(feature = "rand")]
+mod random;
+#[cfg(feature = "rand")]
+pub use self::random::*;
+mod reverse_dns;
+mod scan;
+pub use self::scan::*;
+mod str_buf;
+pub use self::str_buf::*;
+#[cfg(feature = "arrow")]
+mod arrow_impls;
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+mod arbitrary;
+#[cfg(feature = "proptest")]
+mod proptest_impls;
+#[cfg(feature = "quickcheck")]
+mod quickcheck_impls;
+mod batch;
+pub use self::batch::*;
+mod swar;
+mod wire;
+mod nlri;
+pub use self::nlri::*;
+
+#[cfg(all(test, feature = "std"))]
+mod conformance;
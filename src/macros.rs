@@ -0,0 +1,137 @@
+//! Macros to build addresses, masks and networks from string literals at compile time.
+
+/// Build an [`Ipv4Address`](struct.Ipv4Address.html) from a string literal at compile time. An
+/// invalid literal is a build error. See
+/// [`Ipv4Address::parse_const()`](struct.Ipv4Address.html#method.parse_const).
+///
+/// ```rust
+/// # #[macro_use] extern crate ipaddr;
+/// # use ipaddr::Ipv4Address;
+/// # fn main() {
+/// const LOOPBACK: Ipv4Address = ipv4!("127.0.0.1");
+/// assert_eq!(LOOPBACK, Ipv4Address::from(0x7f00_0001));
+///
+/// let ip = ipv4!("192.0.2.33");
+/// assert_eq!(ip.to_string(), "192.0.2.33");
+/// # }
+/// ```
+///
+/// ```rust,compile_fail
+/// # #[macro_use] extern crate ipaddr;
+/// # fn main() {
+/// let ip = ipv4!("192.0.2.256");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! ipv4 {
+    ($s:expr) => {{
+        const VALUE: $crate::Ipv4Address = $crate::Ipv4Address::parse_const($s);
+        VALUE
+    }};
+}
+
+/// Build an [`Ipv6Address`](struct.Ipv6Address.html) from a string literal at compile time. An
+/// invalid literal is a build error. See
+/// [`Ipv6Address::parse_const()`](struct.Ipv6Address.html#method.parse_const).
+///
+/// ```rust
+/// # #[macro_use] extern crate ipaddr;
+/// # use ipaddr::Ipv6Address;
+/// # fn main() {
+/// const ALL_NODES: Ipv6Address = ipv6!("ff02::1");
+/// assert_eq!(ALL_NODES, Ipv6Address::from(0xff02_0000_0000_0000_0000_0000_0000_0001));
+///
+/// let ip = ipv6!("2001:db8::1");
+/// assert_eq!(ip.to_string(), "2001:db8::1");
+/// # }
+/// ```
+///
+/// ```rust,compile_fail
+/// # #[macro_use] extern crate ipaddr;
+/// # fn main() {
+/// let ip = ipv6!("2001:db8::1::2");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! ipv6 {
+    ($s:expr) => {{
+        const VALUE: $crate::Ipv6Address = $crate::Ipv6Address::parse_const($s);
+        VALUE
+    }};
+}
+
+/// Build an [`Ipv4Mask`](struct.Ipv4Mask.html) from a string literal at compile time. An invalid
+/// literal is a build error. See
+/// [`Ipv4Mask::parse_const()`](struct.Ipv4Mask.html#method.parse_const).
+///
+/// ```rust
+/// # #[macro_use] extern crate ipaddr;
+/// # fn main() {
+/// assert_eq!(ipv4_mask!("255.255.255.0").prefixlen(), 24);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! ipv4_mask {
+    ($s:expr) => {{
+        const VALUE: $crate::Ipv4Mask = $crate::Ipv4Mask::parse_const($s);
+        VALUE
+    }};
+}
+
+/// Build an [`Ipv6Mask`](struct.Ipv6Mask.html) from a string literal at compile time. An invalid
+/// literal is a build error. See
+/// [`Ipv6Mask::parse_const()`](struct.Ipv6Mask.html#method.parse_const).
+///
+/// ```rust
+/// # #[macro_use] extern crate ipaddr;
+/// # fn main() {
+/// assert_eq!(ipv6_mask!("ffff:ffff::").prefixlen(), 32);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! ipv6_mask {
+    ($s:expr) => {{
+        const VALUE: $crate::Ipv6Mask = $crate::Ipv6Mask::parse_const($s);
+        VALUE
+    }};
+}
+
+/// Build an [`Ipv4Network`](struct.Ipv4Network.html) from a string literal at compile time. An
+/// invalid literal is a build error. See
+/// [`Ipv4Network::parse_const()`](struct.Ipv4Network.html#method.parse_const).
+///
+/// ```rust
+/// # #[macro_use] extern crate ipaddr;
+/// # fn main() {
+/// let network = ipv4_network!("192.0.2.0/24");
+/// assert_eq!(network.mask(), ipv4_mask!("255.255.255.0"));
+/// assert_eq!(network.network(), ipv4!("192.0.2.0"));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! ipv4_network {
+    ($s:expr) => {{
+        const VALUE: $crate::Ipv4Network = $crate::Ipv4Network::parse_const($s);
+        VALUE
+    }};
+}
+
+/// Build an [`Ipv6Network`](struct.Ipv6Network.html) from a string literal at compile time. An
+/// invalid literal is a build error. See
+/// [`Ipv6Network::parse_const()`](struct.Ipv6Network.html#method.parse_const).
+///
+/// ```rust
+/// # #[macro_use] extern crate ipaddr;
+/// # fn main() {
+/// let network = ipv6_network!("2001:db8::/32");
+/// assert_eq!(network.mask(), ipv6_mask!("ffff:ffff::"));
+/// assert_eq!(network.network(), ipv6!("2001:db8::"));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! ipv6_network {
+    ($s:expr) => {{
+        const VALUE: $crate::Ipv6Network = $crate::Ipv6Network::parse_const($s);
+        VALUE
+    }};
+}
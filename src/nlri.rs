@@ -0,0 +1,304 @@
+//! BGP Network Layer Reachability Information: lists of packed prefixes, as found in the NLRI and
+//! withdrawn routes fields of UPDATE messages (see
+//! [RFC4271](https://tools.ietf.org/html/rfc4271#section-4.3)), and in the `MP_REACH_NLRI` and
+//! `MP_UNREACH_NLRI` attributes (see [RFC4760](https://tools.ietf.org/html/rfc4760#section-5)).
+//!
+//! When the ADD-PATH capability is negotiated (see
+//! [RFC7911](https://tools.ietf.org/html/rfc7911#section-3)), each prefix is preceded by a 4 bytes
+//! path identifier.
+
+use core::marker::PhantomData;
+
+use {BufferTooSmall, Ipv4Network, Ipv6Network, MalformedAddress};
+
+/// A network that can be encoded in the BGP NLRI prefix form: a prefix length byte, followed by
+/// the minimal number of octets that contain the prefix. This is implemented by
+/// [`Ipv4Network`](struct.Ipv4Network.html) and [`Ipv6Network`](struct.Ipv6Network.html).
+pub trait NlriPrefix: Sized {
+    /// Read a prefix, and return it along with the remaining bytes.
+    fn read_prefix(bytes: &[u8]) -> Result<(Self, &[u8]), MalformedAddress>;
+
+    /// Write the prefix, and return the remaining bytes.
+    fn write_prefix<'a>(&self, buf: &'a mut [u8]) -> Result<&'a mut [u8], BufferTooSmall>;
+
+    /// Return the number of bytes of the encoded prefix.
+    fn prefix_encoded_len(&self) -> usize;
+}
+
+impl NlriPrefix for Ipv4Network {
+    fn read_prefix(bytes: &[u8]) -> Result<(Self, &[u8]), MalformedAddress> {
+        Ipv4Network::read_prefix(bytes)
+    }
+
+    fn write_prefix<'a>(&self, buf: &'a mut [u8]) -> Result<&'a mut [u8], BufferTooSmall> {
+        Ipv4Network::write_prefix(self, buf)
+    }
+
+    fn prefix_encoded_len(&self) -> usize {
+        Ipv4Network::prefix_encoded_len(self)
+    }
+}
+
+impl NlriPrefix for Ipv6Network {
+    fn read_prefix(bytes: &[u8]) -> Result<(Self, &[u8]), MalformedAddress> {
+        Ipv6Network::read_prefix(bytes)
+    }
+
+    fn write_prefix<'a>(&self, buf: &'a mut [u8]) -> Result<&'a mut [u8], BufferTooSmall> {
+        Ipv6Network::write_prefix(self, buf)
+    }
+
+    fn prefix_encoded_len(&self) -> usize {
+        Ipv6Network::prefix_encoded_len(self)
+    }
+}
+
+/// A prefix, with an optional ADD-PATH path identifier.
+///
+/// ```rust
+/// # use ipaddr::{Ipv4Network, Nlri};
+/// # use std::str::FromStr;
+/// # fn main() {
+/// let net = Ipv4Network::from_str("10.0.0.0/8").unwrap();
+/// let nlri = Nlri::new(net).with_path_id(1);
+///
+/// let mut buf = [0; 16];
+/// let len = buf.len() - nlri.write_to(&mut buf).unwrap().len();
+/// assert_eq!(len, nlri.encoded_len());
+/// assert_eq!(&buf[..len], &[0, 0, 0, 1, 8, 10]);
+///
+/// let (decoded, rest) = Nlri::<Ipv4Network>::read_from(&buf[..len], true).unwrap();
+/// assert_eq!(decoded, nlri);
+/// assert!(rest.is_empty());
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Nlri<N> {
+    path_id: Option<u32>,
+    prefix: N,
+}
+
+impl<N: NlriPrefix> Nlri<N> {
+    /// Create an NLRI entry without path identifier.
+    pub fn new(prefix: N) -> Self {
+        Nlri {
+            path_id: None,
+            prefix,
+        }
+    }
+
+    /// Set the ADD-PATH path identifier.
+    pub fn with_path_id(mut self, path_id: u32) -> Self {
+        self.path_id = Some(path_id);
+        self
+    }
+
+    /// Return the ADD-PATH path identifier, if any.
+    pub fn path_id(&self) -> Option<u32> {
+        self.path_id
+    }
+
+    /// Return the prefix.
+    pub fn prefix(&self) -> &N {
+        &self.prefix
+    }
+
+    /// Read an NLRI entry, and return it along with the remaining bytes. If `add_path` is `true`,
+    /// the prefix is expected to be preceded by a path identifier.
+    pub fn read_from(bytes: &[u8], add_path: bool) -> Result<(Self, &[u8]), MalformedAddress> {
+        let (path_id, bytes) = if add_path {
+            if bytes.len() < 4 {
+                return Err(MalformedAddress);
+            }
+            let (path_id, rest) = bytes.split_at(4);
+            let path_id = (u32::from(path_id[0]) << 24)
+                | (u32::from(path_id[1]) << 16)
+                | (u32::from(path_id[2]) << 8)
+                | u32::from(path_id[3]);
+            (Some(path_id), rest)
+        } else {
+            (None, bytes)
+        };
+        let (prefix, rest) = N::read_prefix(bytes)?;
+        Ok((Nlri { path_id, prefix }, rest))
+    }
+
+    /// Write the NLRI entry, and return the remaining bytes. The path identifier is written only
+    /// if it is set.
+    pub fn write_to<'a>(&self, buf: &'a mut [u8]) -> Result<&'a mut [u8], BufferTooSmall> {
+        if buf.len() < self.encoded_len() {
+            return Err(BufferTooSmall);
+        }
+        let buf = match self.path_id {
+            Some(path_id) => {
+                let (head, rest) = buf.split_at_mut(4);
+                head[0] = (path_id >> 24) as u8;
+                head[1] = (path_id >> 16) as u8;
+                head[2] = (path_id >> 8) as u8;
+                head[3] = path_id as u8;
+                rest
+            }
+            None => buf,
+        };
+        self.prefix.write_prefix(buf)
+    }
+
+    /// Return the number of bytes of the encoded NLRI entry.
+    pub fn encoded_len(&self) -> usize {
+        let path_id_len = if self.path_id.is_some() { 4 } else { 0 };
+        path_id_len + self.prefix.prefix_encoded_len()
+    }
+}
+
+/// An iterator over a buffer of packed NLRI entries. If an entry is malformed, the iterator
+/// returns an error and stops.
+///
+/// ```rust
+/// # use ipaddr::{Ipv6Network, NlriReader};
+/// # use std::str::FromStr;
+/// # fn main() {
+/// let bytes = [32, 0x20, 0x01, 0x0d, 0xb8, 0, 16, 0xfe, 0x80];
+/// let prefixes: Vec<_> = NlriReader::<Ipv6Network>::new(&bytes)
+///     .map(|nlri| *nlri.unwrap().prefix())
+///     .collect();
+/// assert_eq!(
+///     prefixes,
+///     vec![
+///         Ipv6Network::from_str("2001:db8::/32").unwrap(),
+///         Ipv6Network::from_str("::/0").unwrap(),
+///         Ipv6Network::from_str("fe80::/16").unwrap(),
+///     ]
+/// );
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct NlriReader<'a, N> {
+    bytes: &'a [u8],
+    add_path: bool,
+    marker: PhantomData<N>,
+}
+
+impl<'a, N: NlriPrefix> NlriReader<'a, N> {
+    /// Create an iterator over the NLRI entries packed in `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        NlriReader {
+            bytes,
+            add_path: false,
+            marker: PhantomData,
+        }
+    }
+
+    /// Set whether each prefix is preceded by an ADD-PATH path identifier. This is `false` by
+    /// default.
+    pub fn add_path(mut self, flag: bool) -> Self {
+        self.add_path = flag;
+        self
+    }
+
+    /// Return the bytes that have not been read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+impl<'a, N: NlriPrefix> Iterator for NlriReader<'a, N> {
+    type Item = Result<Nlri<N>, MalformedAddress>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        match Nlri::read_from(self.bytes, self.add_path) {
+            Ok((nlri, rest)) => {
+                self.bytes = rest;
+                Some(Ok(nlri))
+            }
+            Err(e) => {
+                self.bytes = &[];
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    fn net4(s: &str) -> Ipv4Network {
+        Ipv4Network::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_reader() {
+        let bytes = [24, 192, 0, 2, 0, 32, 10, 0, 0, 1];
+        let nlris: Vec<_> = NlriReader::<Ipv4Network>::new(&bytes)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(
+            nlris,
+            vec![
+                Nlri::new(net4("192.0.2.0/24")),
+                Nlri::new(net4("0.0.0.0/0")),
+                Nlri::new(net4("10.0.0.1/32")),
+            ]
+        );
+
+        // the last prefix is truncated
+        let mut reader = NlriReader::<Ipv4Network>::new(&bytes[..9]);
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_ok());
+        assert_eq!(reader.remaining(), &[32, 10, 0, 0]);
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_add_path() {
+        let nlris = [
+            Nlri::new(net4("192.0.2.0/24")).with_path_id(1),
+            Nlri::new(net4("192.0.2.0/24")).with_path_id(0x0102_0304),
+        ];
+        let mut buf = [0; 32];
+        let len = {
+            let mut rest = &mut buf[..];
+            for nlri in &nlris {
+                rest = nlri.write_to(rest).unwrap();
+            }
+            32 - rest.len()
+        };
+        assert_eq!(
+            &buf[..len],
+            &[0, 0, 0, 1, 24, 192, 0, 2, 1, 2, 3, 4, 24, 192, 0, 2]
+        );
+
+        let decoded: Vec<_> = NlriReader::new(&buf[..len])
+            .add_path(true)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(decoded, nlris);
+
+        // without ADD-PATH, the path identifier is read as a /0 prefix followed by garbage
+        let mut reader = NlriReader::<Ipv4Network>::new(&buf[..len]);
+        assert_eq!(
+            reader.next().unwrap().unwrap(),
+            Nlri::new(net4("0.0.0.0/0"))
+        );
+
+        assert!(Nlri::<Ipv4Network>::read_from(&[0, 0, 1], true).is_err());
+        assert!(nlris[0].write_to(&mut [0; 7]).is_err());
+    }
+
+    #[test]
+    fn test_ipv6() {
+        let net = Ipv6Network::from_str("2001:db8:8000::/33").unwrap();
+        let nlri = Nlri::new(net).with_path_id(7);
+        let mut buf = [0; 21];
+        let rest_len = nlri.write_to(&mut buf).unwrap().len();
+        assert_eq!(buf.len() - rest_len, nlri.encoded_len());
+        assert_eq!(&buf[..10], &[0, 0, 0, 7, 33, 0x20, 0x01, 0x0d, 0xb8, 0x80]);
+        let (decoded, _) = Nlri::<Ipv6Network>::read_from(&buf, true).unwrap();
+        assert_eq!(decoded, nlri);
+    }
+}
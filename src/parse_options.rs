@@ -0,0 +1,310 @@
+//! Options that control which syntaxes are accepted when parsing addresses.
+
+use {ParseError, ParseErrorReason};
+
+/// A builder to configure how [`Ipv4Address::parse_with()`](struct.Ipv4Address.html) and
+/// [`Ipv6Address::parse_with()`](struct.Ipv6Address.html) parse strings.
+///
+/// [`ParseOptions::new()`](#method.new) accepts exactly the same syntax as `from_str()`. Use
+/// [`ParseOptions::strict()`](#method.strict) to reject ambiguous inputs, like IPv4 octets with
+/// leading zeros that some stacks interpret as octal numbers.
+///
+/// ```rust
+/// # use ipaddr::{Ipv4Address, Ipv6Address, ParseOptions};
+/// # use std::str::FromStr;
+/// # fn main() {
+/// let options = ParseOptions::strict();
+/// assert!(Ipv4Address::parse_with("10.1.1.1", &options).is_ok());
+/// assert!(Ipv4Address::parse_with("010.1.1.1", &options).is_err());
+///
+/// let options = ParseOptions::new().brackets(true).whitespace(true).zone_ids(true);
+/// assert_eq!(
+///     Ipv6Address::parse_with(" [fe80::1%eth0] ", &options).unwrap(),
+///     Ipv6Address::from_str("fe80::1").unwrap()
+/// );
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    pub(crate) leading_zeros: bool,
+    pub(crate) embedded_ipv4: bool,
+    pub(crate) uppercase_hex: bool,
+    pub(crate) brackets: bool,
+    pub(crate) whitespace: bool,
+    pub(crate) zone_ids: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions::new()
+    }
+}
+
+impl ParseOptions {
+    /// Return the options that correspond to what `from_str()` accepts: leading zeros, embedded
+    /// IPv4 addresses and upper case hexadecimal digits are allowed, but brackets, surrounding
+    /// whitespaces and zone identifiers are not.
+    pub const fn new() -> Self {
+        ParseOptions {
+            leading_zeros: true,
+            embedded_ipv4: true,
+            uppercase_hex: true,
+            brackets: false,
+            whitespace: false,
+            zone_ids: false,
+        }
+    }
+
+    /// Return options that reject anything ambiguous. This is the same as
+    /// [`new()`](#method.new), but leading zeros are rejected.
+    ///
+    /// ```rust
+    /// # use ipaddr::{Ipv4Address, Ipv6Address, ParseErrorReason, ParseOptions};
+    /// # fn main() {
+    /// let strict = ParseOptions::strict();
+    /// assert!(Ipv4Address::parse_with("10.0.0.1", &strict).is_ok());
+    /// assert!(Ipv6Address::parse_with("::ffff:10.0.0.1", &strict).is_ok());
+    ///
+    /// let err = Ipv4Address::parse_with("010.0.0.1", &strict).unwrap_err();
+    /// assert_eq!(err.reason(), ParseErrorReason::LeadingZero);
+    /// let err = Ipv6Address::parse_with("::ffff:010.0.0.1", &strict).unwrap_err();
+    /// assert_eq!(err.reason(), ParseErrorReason::LeadingZero);
+    /// # }
+    /// ```
+    pub const fn strict() -> Self {
+        ParseOptions::new().leading_zeros(false)
+    }
+
+    /// Return options that accept all the syntaxes supported by this builder.
+    pub const fn lenient() -> Self {
+        ParseOptions::new()
+            .brackets(true)
+            .whitespace(true)
+            .zone_ids(true)
+    }
+
+    /// Accept IPv4 octets with leading zeros, like `010.1.1.1`. They are read as decimal numbers,
+    /// but some stacks read them as octal numbers (see
+    /// [`Ipv4Address::parse_inet_aton()`](struct.Ipv4Address.html#method.parse_inet_aton)). This
+    /// also applies to IPv4 addresses embedded in IPv6 addresses. Leading zeros in IPv6 hextets
+    /// are not ambiguous and are always accepted. This is `true` by default.
+    pub const fn leading_zeros(mut self, flag: bool) -> Self {
+        self.leading_zeros = flag;
+        self
+    }
+
+    /// Accept IPv6 addresses that end with an IPv4 address, like `::ffff:192.0.2.33`. This is
+    /// `true` by default.
+    pub const fn embedded_ipv4(mut self, flag: bool) -> Self {
+        self.embedded_ipv4 = flag;
+        self
+    }
+
+    /// Accept upper case hexadecimal digits in IPv6 addresses, like `FE80::1`. This is `true` by
+    /// default.
+    pub const fn uppercase_hex(mut self, flag: bool) -> Self {
+        self.uppercase_hex = flag;
+        self
+    }
+
+    /// Accept IPv6 addresses surrounded by brackets, like `[::1]`, as they appear in URIs. This is
+    /// `false` by default.
+    pub const fn brackets(mut self, flag: bool) -> Self {
+        self.brackets = flag;
+        self
+    }
+
+    /// Accept leading and trailing ASCII whitespaces. This is `false` by default.
+    pub const fn whitespace(mut self, flag: bool) -> Self {
+        self.whitespace = flag;
+        self
+    }
+
+    /// Accept a zone identifier after an IPv6 address, like `fe80::1%eth0`. The zone identifier
+    /// is discarded: use [`ScopedIpv6Address`](struct.ScopedIpv6Address.html) to keep it. This is
+    /// `false` by default.
+    pub const fn zone_ids(mut self, flag: bool) -> Self {
+        self.zone_ids = flag;
+        self
+    }
+
+    /// Remove the surrounding whitespaces if they are allowed, and return the offset of the
+    /// remaining bytes.
+    pub(crate) fn trim<'a>(&self, bytes: &'a [u8]) -> (usize, &'a [u8]) {
+        if !self.whitespace {
+            return (0, bytes);
+        }
+        let start = bytes
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())
+            .unwrap_or(bytes.len());
+        let end = bytes
+            .iter()
+            .rposition(|b| !b.is_ascii_whitespace())
+            .map_or(start, |i| i + 1);
+        (start, &bytes[start..end])
+    }
+
+    /// Remove the surrounding brackets and the zone identifier of an IPv6 address if they are
+    /// allowed, and return the offset of the remaining bytes.
+    pub(crate) fn strip_ipv6_decorations<'a>(
+        &self,
+        bytes: &'a [u8],
+    ) -> Result<(usize, &'a [u8]), ParseError> {
+        let (start, mut bytes) = match bytes.first() {
+            Some(b'[') if self.brackets => match bytes.last() {
+                Some(b']') if bytes.len() > 1 => (1, &bytes[1..bytes.len() - 1]),
+                _ => {
+                    return Err(ParseError::new(
+                        bytes.len(),
+                        ParseErrorReason::UnexpectedEnd,
+                    ))
+                }
+            },
+            _ => (0, bytes),
+        };
+        if self.zone_ids {
+            if let Some(delimiter) = bytes.iter().position(|b| *b == b'%') {
+                if delimiter + 1 == bytes.len() {
+                    return Err(ParseError::new(
+                        start + bytes.len(),
+                        ParseErrorReason::UnexpectedEnd,
+                    ));
+                }
+                bytes = &bytes[..delimiter];
+            }
+        }
+        Ok((start, bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+    use {Ipv4Address, Ipv6Address};
+
+    fn ipv4_error(s: &str, options: &ParseOptions) -> (usize, ParseErrorReason) {
+        let err = Ipv4Address::parse_with(s, options).unwrap_err();
+        (err.offset(), err.reason())
+    }
+
+    fn ipv6_error(s: &str, options: &ParseOptions) -> (usize, ParseErrorReason) {
+        let err = Ipv6Address::parse_with(s, options).unwrap_err();
+        (err.offset(), err.reason())
+    }
+
+    #[test]
+    fn test_default() {
+        let options = ParseOptions::new();
+        for s in &["0.0.0.0", "010.1.1.1", "255.255.255.255"] {
+            assert_eq!(
+                Ipv4Address::parse_with(s, &options).unwrap(),
+                Ipv4Address::from_str(s).unwrap()
+            );
+        }
+        for s in &["::", "FE80::1", "::ffff:010.0.0.1", "0000:0::1"] {
+            assert_eq!(
+                Ipv6Address::parse_with(s, &options).unwrap(),
+                Ipv6Address::from_str(s).unwrap()
+            );
+        }
+        assert_eq!(
+            ipv4_error(" 1.2.3.4", &options),
+            (0, ParseErrorReason::InvalidCharacter)
+        );
+        assert_eq!(
+            ipv6_error("[::1]", &options),
+            (0, ParseErrorReason::InvalidCharacter)
+        );
+        assert_eq!(
+            ipv6_error("fe80::1%1", &options),
+            (7, ParseErrorReason::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn test_leading_zeros() {
+        let options = ParseOptions::strict();
+        assert!(Ipv4Address::parse_with("0.0.0.0", &options).is_ok());
+        assert!(Ipv4Address::parse_with("10.100.0.1", &options).is_ok());
+        assert_eq!(
+            ipv4_error("010.1.1.1", &options),
+            (0, ParseErrorReason::LeadingZero)
+        );
+        assert_eq!(
+            ipv4_error("1.1.1.00", &options),
+            (6, ParseErrorReason::LeadingZero)
+        );
+        assert_eq!(
+            ipv6_error("::ffff:1.2.3.04", &options),
+            (13, ParseErrorReason::LeadingZero)
+        );
+        assert_eq!(
+            ipv6_error("::ffff:1.02.3.4", &options),
+            (9, ParseErrorReason::LeadingZero)
+        );
+        // other errors in the embedded IPv4 address are still reported as such
+        assert_eq!(
+            ipv6_error("::ffff:1.2.3.256", &options),
+            (13, ParseErrorReason::InvalidEmbeddedIpv4)
+        );
+        // leading zeros in hextets are fine
+        assert!(Ipv6Address::parse_with("0000:0::0001", &options).is_ok());
+    }
+
+    #[test]
+    fn test_ipv6_syntax() {
+        let options = ParseOptions::new()
+            .embedded_ipv4(false)
+            .uppercase_hex(false);
+        assert!(Ipv6Address::parse_with("fe80::1", &options).is_ok());
+        assert_eq!(
+            ipv6_error("::1.2.3.4", &options),
+            (3, ParseErrorReason::InvalidCharacter)
+        );
+        assert_eq!(
+            ipv6_error("Fe80::1", &options),
+            (0, ParseErrorReason::InvalidCharacter)
+        );
+        assert_eq!(
+            ipv6_error("fE80::1", &options),
+            (1, ParseErrorReason::InvalidCharacter)
+        );
+        assert_eq!(
+            ipv6_error("fe80::abcD", &options),
+            (9, ParseErrorReason::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn test_lenient() {
+        let options = ParseOptions::lenient();
+        let ip = Ipv6Address::from_str("fe80::1").unwrap();
+        for s in &["fe80::1", "[fe80::1]", " fe80::1%eth0\t", "\n[fe80::1%1] "] {
+            assert_eq!(Ipv6Address::parse_with(s, &options).unwrap(), ip);
+        }
+        assert_eq!(
+            Ipv4Address::parse_with(" 1.2.3.4 ", &options).unwrap(),
+            Ipv4Address::from_str("1.2.3.4").unwrap()
+        );
+        assert_eq!(ipv6_error("", &options), (0, ParseErrorReason::Empty));
+        assert_eq!(ipv6_error("  ", &options), (2, ParseErrorReason::Empty));
+        assert_eq!(
+            ipv6_error(" [fe80::1 ", &options),
+            (9, ParseErrorReason::UnexpectedEnd)
+        );
+        assert_eq!(
+            ipv6_error(" fe80::1%", &options),
+            (9, ParseErrorReason::UnexpectedEnd)
+        );
+        assert_eq!(
+            ipv6_error("[fe80::g]", &options),
+            (7, ParseErrorReason::InvalidCharacter)
+        );
+        assert_eq!(
+            ipv6_error(" fe80::g", &options),
+            (7, ParseErrorReason::InvalidCharacter)
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! [`proptest`](https://docs.rs/proptest) strategies. They are biased toward edge cases, like the
+//! `/0`, `/31`, `/32`, `/127` and `/128` prefixes, IPv4-mapped addresses, multicast addresses and
+//! addresses with runs of zero hextets.
+
+use proptest::arbitrary::{any, Arbitrary};
+use proptest::strategy::{BoxedStrategy, Strategy};
+
+use arbitrary::*;
+use {Ipv4Address, Ipv4Mask, Ipv4Network, Ipv6Address, Ipv6Mask, Ipv6Network};
+
+impl Arbitrary for Ipv4Address {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (0..IPV4_KINDS, any::<u32>(), any::<u8>())
+            .prop_map(|(kind, bits, prefixlen)| ipv4_address(kind, bits, prefixlen))
+            .boxed()
+    }
+}
+
+impl Arbitrary for Ipv6Address {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (0..IPV6_KINDS, any::<u128>(), any::<u8>())
+            .prop_map(|(kind, bits, prefixlen)| ipv6_address(kind, bits, prefixlen))
+            .boxed()
+    }
+}
+
+impl Arbitrary for Ipv4Mask {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (0..PREFIXLEN_KINDS, any::<u8>())
+            .prop_map(|(kind, len)| Ipv4Mask::from_prefixlen(ipv4_prefixlen(kind, len)).unwrap())
+            .boxed()
+    }
+}
+
+impl Arbitrary for Ipv6Mask {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (0..PREFIXLEN_KINDS, any::<u8>())
+            .prop_map(|(kind, len)| Ipv6Mask::from_prefixlen(ipv6_prefixlen(kind, len)).unwrap())
+            .boxed()
+    }
+}
+
+impl Arbitrary for Ipv4Network {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            any::<Ipv4Address>(),
+            0..PREFIXLEN_KINDS,
+            any::<u8>(),
+            any::<bool>(),
+        )
+            .prop_map(|(ip, kind, len, aligned)| {
+                ipv4_network(ip, ipv4_prefixlen(kind, len), aligned)
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Ipv6Network {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            any::<Ipv6Address>(),
+            0..PREFIXLEN_KINDS,
+            any::<u8>(),
+            any::<bool>(),
+        )
+            .prop_map(|(ip, kind, len, aligned)| {
+                ipv6_network(ip, ipv6_prefixlen(kind, len), aligned)
+            })
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Alignment;
+    use core::str::FromStr;
+    use proptest::option;
+    use proptest::prelude::*;
+    use {Ipv4Notation, Ipv4Suffix, Ipv6Zone, ScopedIpv6Address};
+
+    fn ipv4_notation() -> impl Strategy<Value = Ipv4Notation> {
+        prop_oneof![
+            Just(Ipv4Notation::DottedDecimal),
+            Just(Ipv4Notation::Hexadecimal),
+            Just(Ipv4Notation::Integer),
+            Just(Ipv4Notation::Octal),
+            Just(Ipv4Notation::Binary),
+            Just(Ipv4Notation::DottedBinary),
+        ]
+    }
+
+    fn ipv4_suffix() -> impl Strategy<Value = Ipv4Suffix> {
+        prop_oneof![
+            Just(Ipv4Suffix::Never),
+            Just(Ipv4Suffix::Always),
+            Just(Ipv4Suffix::Auto),
+        ]
+    }
+
+    fn alignment() -> impl Strategy<Value = Alignment> {
+        prop_oneof![
+            Just(Alignment::Left),
+            Just(Alignment::Right),
+            Just(Alignment::Center),
+        ]
+    }
+
+    fn ipv6_zone() -> impl Strategy<Value = Ipv6Zone> {
+        prop_oneof![
+            any::<u32>().prop_map(Ipv6Zone::Index),
+            // Names that only contain digits would be read as indices
+            "[a-zA-Z_%é][a-zA-Z0-9._~%/:é -]{0,15}".prop_map(Ipv6Zone::Name),
+        ]
+    }
+
+    /// Parse an IPv4 address written in any of the notations of `Ipv4Formatter`.
+    fn parse_ipv4(s: &str, notation: Ipv4Notation) -> Option<Ipv4Address> {
+        match notation {
+            Ipv4Notation::DottedDecimal => Ipv4Address::from_str(s).ok(),
+            Ipv4Notation::Hexadecimal | Ipv4Notation::Octal => {
+                Ipv4Address::parse_inet_aton(s).ok().map(|(ip, _)| ip)
+            }
+            // With leading zeros, this is not the octal notation of `inet_aton()`
+            Ipv4Notation::Integer => u32::from_str(s).ok().map(Ipv4Address::from),
+            Ipv4Notation::Binary => {
+                if !s.starts_with("0b") {
+                    return None;
+                }
+                u32::from_str_radix(&s[2..], 2).ok().map(Ipv4Address::from)
+            }
+            Ipv4Notation::DottedBinary => {
+                let mut octets = [0; 4];
+                let mut parts = s.split('.');
+                for octet in &mut octets {
+                    let part = parts.next()?;
+                    if part.len() != 8 {
+                        return None;
+                    }
+                    *octet = u8::from_str_radix(part, 2).ok()?;
+                }
+                if parts.next().is_some() {
+                    return None;
+                }
+                Some(Ipv4Address::from_slice_unchecked(&octets))
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn ipv4_formatter_round_trip(
+            ip in any::<Ipv4Address>(),
+            notation in ipv4_notation(),
+            leading_zeros in any::<bool>(),
+            upper_case in any::<bool>(),
+            width in 0..40usize,
+            align in alignment(),
+        ) {
+            let mut s = String::new();
+            ip.formatter(&mut s)
+                .notation(notation)
+                .leading_zeros(leading_zeros)
+                .upper_case(upper_case)
+                .width(width)
+                .align(align)
+                .fill('*')
+                .write()
+                .unwrap();
+            prop_assert!(s.len() >= width);
+            prop_assert_eq!(parse_ipv4(s.trim_matches('*'), notation), Some(ip));
+        }
+
+        #[test]
+        fn ipv6_formatter_round_trip(
+            ip in any::<Ipv6Address>(),
+            ellipsis in any::<bool>(),
+            leading_zeros in any::<bool>(),
+            upper_case in any::<bool>(),
+            suffix in ipv4_suffix(),
+            zone in option::of(ipv6_zone()),
+            url_encoded_zone in any::<bool>(),
+        ) {
+            let scoped = zone.map(|zone| ScopedIpv6Address::new(ip, zone));
+            let mut s = String::new();
+            let formatter = match scoped {
+                Some(ref scoped) => scoped.formatter(&mut s),
+                None => ip.formatter(&mut s),
+            };
+            formatter
+                .ellipsis(ellipsis)
+                .leading_zeros(leading_zeros)
+                .upper_case(upper_case)
+                .ipv4_suffix(suffix)
+                .url_encoded_zone(url_encoded_zone)
+                .write()
+                .unwrap();
+            match scoped {
+                Some(scoped) => {
+                    let parsed = if url_encoded_zone {
+                        ScopedIpv6Address::from_uri_str(&s)
+                    } else {
+                        ScopedIpv6Address::from_str(&s)
+                    };
+                    prop_assert_eq!(parsed.unwrap(), scoped);
+                }
+                None => prop_assert_eq!(Ipv6Address::from_str(&s).unwrap(), ip),
+            }
+        }
+
+        #[test]
+        fn ipv6_display_padding_round_trip(
+            ip in any::<Ipv6Address>(),
+            width in 0..60usize,
+            align in alignment(),
+            alternate in any::<bool>(),
+        ) {
+            let s = match (align, alternate) {
+                (Alignment::Left, false) => format!("{:*<1$}", ip, width),
+                (Alignment::Left, true) => format!("{:*<#1$}", ip, width),
+                (Alignment::Right, false) => format!("{:*>1$}", ip, width),
+                (Alignment::Right, true) => format!("{:*>#1$}", ip, width),
+                (Alignment::Center, false) => format!("{:*^1$}", ip, width),
+                (Alignment::Center, true) => format!("{:*^#1$}", ip, width),
+            };
+            let unpadded = if alternate {
+                format!("{:#}", ip)
+            } else {
+                format!("{}", ip)
+            };
+            prop_assert_eq!(s.len(), width.max(unpadded.len()));
+            prop_assert_eq!(s.trim_matches('*'), &*unpadded);
+            prop_assert_eq!(Ipv6Address::from_str(&unpadded).unwrap(), ip);
+        }
+
+        #[test]
+        fn display_round_trip(
+            ip4 in any::<Ipv4Address>(),
+            ip6 in any::<Ipv6Address>(),
+            mask4 in any::<Ipv4Mask>(),
+            mask6 in any::<Ipv6Mask>(),
+            net4 in any::<Ipv4Network>(),
+            net6 in any::<Ipv6Network>(),
+        ) {
+            prop_assert_eq!(Ipv4Address::from_str(&ip4.to_string()).unwrap(), ip4);
+            prop_assert_eq!(Ipv6Address::from_str(&ip6.to_string()).unwrap(), ip6);
+            prop_assert_eq!(Ipv6Address::from_str(&format!("{:#}", ip6)).unwrap(), ip6);
+            prop_assert_eq!(Ipv4Mask::from_str(&mask4.to_string()).unwrap(), mask4);
+            prop_assert_eq!(Ipv6Mask::from_str(&mask6.to_string()).unwrap(), mask6);
+            prop_assert_eq!(Ipv4Network::from_str(&net4.to_string()).unwrap(), net4);
+            prop_assert_eq!(Ipv6Network::from_str(&net6.to_string()).unwrap(), net6);
+        }
+
+        #[test]
+        fn rfc_5952_is_canonical(ip in any::<Ipv6Address>()) {
+            // Formatting is idempotent: parsing the text again gives the same text.
+            let s = ip.to_string();
+            prop_assert_eq!(Ipv6Address::from_str(&s).unwrap().to_string(), s);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! [`quickcheck`](https://docs.rs/quickcheck) implementations. They are biased toward edge cases,
+//! like the `/0`, `/31`, `/32`, `/127` and `/128` prefixes, IPv4-mapped addresses, multicast
+//! addresses and addresses with runs of zero hextets.
+
+use quickcheck::{Arbitrary, Gen};
+
+use arbitrary::*;
+use {Ipv4Address, Ipv4Mask, Ipv4Network, Ipv6Address, Ipv6Mask, Ipv6Network};
+
+impl Arbitrary for Ipv4Address {
+    fn arbitrary(g: &mut Gen) -> Self {
+        ipv4_address(
+            u8::arbitrary(g) % IPV4_KINDS,
+            u32::arbitrary(g),
+            u8::arbitrary(g),
+        )
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.0.shrink().map(Ipv4Address))
+    }
+}
+
+impl Arbitrary for Ipv6Address {
+    fn arbitrary(g: &mut Gen) -> Self {
+        ipv6_address(
+            u8::arbitrary(g) % IPV6_KINDS,
+            u128::arbitrary(g),
+            u8::arbitrary(g),
+        )
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.0.shrink().map(Ipv6Address))
+    }
+}
+
+impl Arbitrary for Ipv4Mask {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let prefixlen = ipv4_prefixlen(u8::arbitrary(g), u8::arbitrary(g));
+        Ipv4Mask::from_prefixlen(prefixlen).unwrap()
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let prefixlen = self.prefixlen() as u8;
+        Box::new(
+            prefixlen
+                .shrink()
+                .map(|len| Ipv4Mask::from_prefixlen(len).unwrap()),
+        )
+    }
+}
+
+impl Arbitrary for Ipv6Mask {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let prefixlen = ipv6_prefixlen(u8::arbitrary(g), u8::arbitrary(g));
+        Ipv6Mask::from_prefixlen(prefixlen).unwrap()
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let prefixlen = self.prefixlen() as u8;
+        Box::new(
+            prefixlen
+                .shrink()
+                .map(|len| Ipv6Mask::from_prefixlen(len).unwrap()),
+        )
+    }
+}
+
+impl Arbitrary for Ipv4Network {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let ip = Ipv4Address::arbitrary(g);
+        let prefixlen = ipv4_prefixlen(u8::arbitrary(g), u8::arbitrary(g));
+        ipv4_network(ip, prefixlen, bool::arbitrary(g))
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let (ip, mask) = (self.0, self.1);
+        Box::new(
+            (ip, mask)
+                .shrink()
+                .map(|(ip, mask)| Ipv4Network::new(ip, mask)),
+        )
+    }
+}
+
+impl Arbitrary for Ipv6Network {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let ip = Ipv6Address::arbitrary(g);
+        let prefixlen = ipv6_prefixlen(u8::arbitrary(g), u8::arbitrary(g));
+        ipv6_network(ip, prefixlen, bool::arbitrary(g))
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let (ip, mask) = (self.0, self.1);
+        Box::new(
+            (ip, mask)
+                .shrink()
+                .map(|(ip, mask)| Ipv6Network::new(ip, mask)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+    use quickcheck::quickcheck;
+
+    #[test]
+    fn test_display_round_trip() {
+        fn prop(ip4: Ipv4Address, ip6: Ipv6Address, net4: Ipv4Network, net6: Ipv6Network) -> bool {
+            Ipv4Address::from_str(&ip4.to_string()).unwrap() == ip4
+                && Ipv6Address::from_str(&ip6.to_string()).unwrap() == ip6
+                && Ipv4Network::from_str(&net4.to_string()).unwrap() == net4
+                && Ipv6Network::from_str(&net6.to_string()).unwrap() == net6
+        }
+        quickcheck(prop as fn(Ipv4Address, Ipv6Address, Ipv4Network, Ipv6Network) -> bool);
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        // The bias must actually produce the edge cases, in a reasonable number of draws.
+        let mut g = Gen::new(100);
+        let (mut slash_31, mut slash_127, mut mapped, mut multicast) = (false, false, false, false);
+        for _ in 0..1000 {
+            slash_31 |= Ipv4Mask::arbitrary(&mut g).prefixlen() == 31;
+            slash_127 |= Ipv6Mask::arbitrary(&mut g).prefixlen() == 127;
+            let ip = Ipv6Address::arbitrary(&mut g);
+            mapped |= ip.0 >> 32 == 0xffff;
+            multicast |= ip.0 >> 120 == 0xff;
+        }
+        assert!(slash_31 && slash_127 && mapped && multicast);
+    }
+}
@@ -0,0 +1,383 @@
+//! Random generation of addresses and subnets within a network.
+
+use rand::Rng;
+
+use {
+    InvalidMask, Ipv4Address, Ipv4Mask, Ipv4Network, Ipv6Address, Ipv6Mask, Ipv6Network,
+    IPV4_MAX_PREFIXLEN, IPV6_MAX_PREFIXLEN,
+};
+
+/// A builder to configure which addresses
+/// [`Ipv4Network::random_address_with()`](struct.Ipv4Network.html#method.random_address_with) and
+/// [`Ipv6Network::random_address_with()`](struct.Ipv6Network.html#method.random_address_with)
+/// never return. By default, no address is excluded.
+///
+/// Exclusions only apply to networks that have at least 4 addresses, so that point-to-point links
+/// (`/31` in IPv4, see [RFC3021](https://tools.ietf.org/html/rfc3021), and `/127` in IPv6, see
+/// [RFC6164](https://tools.ietf.org/html/rfc6164)) and single addresses can still be used.
+///
+/// ```rust
+/// # extern crate ipaddr;
+/// # extern crate rand;
+/// # use ipaddr::{Ipv4Network, RandomOptions};
+/// # use rand::rngs::SmallRng;
+/// # use rand::SeedableRng;
+/// # use std::str::FromStr;
+/// # fn main() {
+/// let mut rng = SmallRng::seed_from_u64(0);
+/// let options = RandomOptions::new().exclude_network(true).exclude_broadcast(true);
+/// let net = Ipv4Network::from_str("192.0.2.0/30").unwrap();
+/// for _ in 0..100 {
+///     let ip = net.random_address_with(&options, &mut rng);
+///     assert!(ip != net.network() && ip != net.broadcast());
+/// }
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RandomOptions {
+    network: bool,
+    broadcast: bool,
+    anycast: bool,
+}
+
+impl RandomOptions {
+    /// Return options that do not exclude any address.
+    pub const fn new() -> Self {
+        RandomOptions {
+            network: false,
+            broadcast: false,
+            anycast: false,
+        }
+    }
+
+    /// Exclude the network address, i.e. the address with all the host bits unset.
+    pub const fn exclude_network(mut self, flag: bool) -> Self {
+        self.network = flag;
+        self
+    }
+
+    /// Exclude the broadcast address, i.e. the address with all the host bits set. IPv6 has no
+    /// broadcast, but the last address of a subnet is still often avoided.
+    pub const fn exclude_broadcast(mut self, flag: bool) -> Self {
+        self.broadcast = flag;
+        self
+    }
+
+    /// Exclude the IPv6 anycast addresses that are reserved in every subnet:
+    ///
+    /// - the Subnet-Router anycast address, which has an all-zeros interface identifier (see
+    ///   [RFC4291](https://tools.ietf.org/html/rfc4291#section-2.6.1))
+    /// - the reserved subnet anycast addresses (see [RFC2526](https://tools.ietf.org/html/rfc2526)),
+    ///   which are the interface identifiers `fdff:ffff:ffff:ff80` to `fdff:ffff:ffff:ffff` for
+    ///   subnets of `/64` or shorter, and the last 128 addresses for longer subnets of at least 256
+    ///   addresses.
+    ///
+    /// This has no effect on IPv4 networks.
+    pub const fn exclude_anycast(mut self, flag: bool) -> Self {
+        self.anycast = flag;
+        self
+    }
+}
+
+/// Return a uniformly distributed host part in `0..=host_mask`, among the ones that are not
+/// excluded. There must be at least two allowed values in any 256 consecutive values, so that
+/// rejection sampling terminates quickly.
+fn random_host<R, F>(host_mask: u128, excluded: F, rng: &mut R) -> u128
+where
+    R: Rng + ?Sized,
+    F: Fn(u128) -> bool,
+{
+    if host_mask <= 0xff {
+        // Small networks may be mostly excluded: pick among the allowed hosts directly.
+        let count = (0..=host_mask).filter(|host| !excluded(*host)).count();
+        let n = rng.gen_range(0..count);
+        return (0..=host_mask)
+            .filter(|host| !excluded(*host))
+            .nth(n)
+            .expect("no host left in network");
+    }
+    loop {
+        let host = rng.gen::<u128>() & host_mask;
+        if !excluded(host) {
+            return host;
+        }
+    }
+}
+
+impl Ipv4Network {
+    /// Return an address of this network, chosen uniformly at random.
+    ///
+    /// ```rust
+    /// # extern crate ipaddr;
+    /// # extern crate rand;
+    /// # use ipaddr::Ipv4Network;
+    /// # use rand::rngs::SmallRng;
+    /// # use rand::SeedableRng;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let mut rng = SmallRng::seed_from_u64(0);
+    /// let net = Ipv4Network::from_str("10.0.0.0/8").unwrap();
+    /// let ip = net.random_address(&mut rng);
+    /// assert_eq!(ip & net.mask(), net.network());
+    /// # }
+    /// ```
+    pub fn random_address<R: Rng + ?Sized>(&self, rng: &mut R) -> Ipv4Address {
+        self.random_address_with(&RandomOptions::new(), rng)
+    }
+
+    /// Return an address of this network, chosen uniformly at random among the addresses that are
+    /// not excluded by `options`.
+    pub fn random_address_with<R: Rng + ?Sized>(
+        &self,
+        options: &RandomOptions,
+        rng: &mut R,
+    ) -> Ipv4Address {
+        let host_mask = u128::from(self.host_mask().0);
+        let applies = host_mask >= 3;
+        let network = applies && options.network;
+        let broadcast = applies && options.broadcast;
+        let host = random_host(
+            host_mask,
+            |host| (network && host == 0) || (broadcast && host == host_mask),
+            rng,
+        );
+        Ipv4Address(self.network().0 | host as u32)
+    }
+
+    /// Return a subnet of this network with the given prefix length, chosen uniformly at random.
+    /// This fails if `prefixlen` is shorter than the prefix length of this network, or longer than
+    /// 32.
+    ///
+    /// ```rust
+    /// # extern crate ipaddr;
+    /// # extern crate rand;
+    /// # use ipaddr::Ipv4Network;
+    /// # use rand::rngs::SmallRng;
+    /// # use rand::SeedableRng;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let mut rng = SmallRng::seed_from_u64(0);
+    /// let net = Ipv4Network::from_str("10.0.0.0/8").unwrap();
+    /// let subnet = net.random_subnet(24, &mut rng).unwrap();
+    /// assert_eq!(subnet.mask().prefixlen(), 24);
+    /// assert_eq!(subnet.network() & net.mask(), net.network());
+    /// assert!(net.random_subnet(7, &mut rng).is_err());
+    /// # }
+    /// ```
+    pub fn random_subnet<R: Rng + ?Sized>(
+        &self,
+        prefixlen: u8,
+        rng: &mut R,
+    ) -> Result<Self, InvalidMask> {
+        if prefixlen > IPV4_MAX_PREFIXLEN || u32::from(prefixlen) < self.mask().prefixlen() {
+            return Err(InvalidMask);
+        }
+        let mask = Ipv4Mask::from_prefixlen(prefixlen)?;
+        Ok(Ipv4Network::new(self.random_address(rng) & mask, mask))
+    }
+}
+
+/// The interface identifiers of the reserved subnet anycast addresses, for subnets of `/64` or
+/// shorter (see [RFC2526](https://tools.ietf.org/html/rfc2526#section-2)).
+const EUI64_RESERVED_ANYCAST: u64 = 0xfdff_ffff_ffff_ff80;
+
+impl Ipv6Network {
+    /// Return an address of this network, chosen uniformly at random.
+    ///
+    /// ```rust
+    /// # extern crate ipaddr;
+    /// # extern crate rand;
+    /// # use ipaddr::Ipv6Network;
+    /// # use rand::rngs::SmallRng;
+    /// # use rand::SeedableRng;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let mut rng = SmallRng::seed_from_u64(0);
+    /// let net = Ipv6Network::from_str("2001:db8::/32").unwrap();
+    /// let ip = net.random_address(&mut rng);
+    /// assert_eq!(ip & net.mask(), net.network());
+    /// # }
+    /// ```
+    pub fn random_address<R: Rng + ?Sized>(&self, rng: &mut R) -> Ipv6Address {
+        self.random_address_with(&RandomOptions::new(), rng)
+    }
+
+    /// Return an address of this network, chosen uniformly at random among the addresses that are
+    /// not excluded by `options`.
+    ///
+    /// ```rust
+    /// # extern crate ipaddr;
+    /// # extern crate rand;
+    /// # use ipaddr::{Ipv6Network, RandomOptions};
+    /// # use rand::rngs::SmallRng;
+    /// # use rand::SeedableRng;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let mut rng = SmallRng::seed_from_u64(0);
+    /// let options = RandomOptions::new().exclude_anycast(true);
+    /// let net = Ipv6Network::from_str("2001:db8::/120").unwrap();
+    /// for _ in 0..100 {
+    ///     let host = u128::from(net.random_address_with(&options, &mut rng)) & 0xff;
+    ///     assert!(host > 0 && host < 0x80);
+    /// }
+    /// # }
+    /// ```
+    pub fn random_address_with<R: Rng + ?Sized>(
+        &self,
+        options: &RandomOptions,
+        rng: &mut R,
+    ) -> Ipv6Address {
+        let host_mask = self.host_mask().0;
+        let applies = host_mask >= 3;
+        let network = applies && options.network;
+        let broadcast = applies && options.broadcast;
+        let anycast = applies && options.anycast;
+        let short_prefix = self.mask().prefixlen() <= 64;
+        let host = random_host(
+            host_mask,
+            |host| {
+                if (network && host == 0) || (broadcast && host == host_mask) {
+                    return true;
+                }
+                if !anycast {
+                    return false;
+                }
+                if short_prefix {
+                    let interface_id = host as u64;
+                    interface_id == 0 || interface_id & !0x7f == EUI64_RESERVED_ANYCAST
+                } else {
+                    host == 0 || (host_mask >= 0xff && host > host_mask - 0x80)
+                }
+            },
+            rng,
+        );
+        Ipv6Address(self.network().0 | host)
+    }
+
+    /// Return a subnet of this network with the given prefix length, chosen uniformly at random.
+    /// This fails if `prefixlen` is shorter than the prefix length of this network, or longer than
+    /// 128.
+    ///
+    /// ```rust
+    /// # extern crate ipaddr;
+    /// # extern crate rand;
+    /// # use ipaddr::Ipv6Network;
+    /// # use rand::rngs::SmallRng;
+    /// # use rand::SeedableRng;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let mut rng = SmallRng::seed_from_u64(0);
+    /// let net = Ipv6Network::from_str("2001:db8::/32").unwrap();
+    /// let subnet = net.random_subnet(64, &mut rng).unwrap();
+    /// assert_eq!(subnet.mask().prefixlen(), 64);
+    /// assert_eq!(subnet.network() & net.mask(), net.network());
+    /// assert!(net.random_subnet(129, &mut rng).is_err());
+    /// # }
+    /// ```
+    pub fn random_subnet<R: Rng + ?Sized>(
+        &self,
+        prefixlen: u8,
+        rng: &mut R,
+    ) -> Result<Self, InvalidMask> {
+        if prefixlen > IPV6_MAX_PREFIXLEN || u32::from(prefixlen) < self.mask().prefixlen() {
+            return Err(InvalidMask);
+        }
+        let mask = Ipv6Mask::from_prefixlen(prefixlen)?;
+        Ok(Ipv6Network::new(self.random_address(rng) & mask, mask))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_ipv4_small_networks() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let all = RandomOptions::new()
+            .exclude_network(true)
+            .exclude_broadcast(true);
+
+        // Every allowed address of a /29 is eventually returned, and only those.
+        let net = Ipv4Network::from_str("192.0.2.8/29").unwrap();
+        let mut seen = [0; 8];
+        for _ in 0..1000 {
+            let ip = net.random_address_with(&all, &mut rng);
+            seen[(ip.0 - net.network().0) as usize] += 1;
+        }
+        assert_eq!(seen[0], 0);
+        assert_eq!(seen[7], 0);
+        assert!(seen[1..7].iter().all(|count| *count > 100));
+
+        // /31 and /32 have no network and broadcast address.
+        let net = Ipv4Network::from_str("192.0.2.0/31").unwrap();
+        let ip = net.random_address_with(&all, &mut rng);
+        assert_eq!(ip & net.mask(), net.network());
+        let net = Ipv4Network::from_str("192.0.2.1/32").unwrap();
+        assert_eq!(net.random_address_with(&all, &mut rng), *net.ip());
+    }
+
+    #[test]
+    fn test_ipv6_uniform_host_bits() {
+        // Each host bit must be set about half of the time, including the high bits of a /0.
+        let mut rng = SmallRng::seed_from_u64(2);
+        for net in &["::/0", "2001:db8::/33", "2001:db8::/97"] {
+            let net = Ipv6Network::from_str(net).unwrap();
+            let host_mask = net.host_mask().0;
+            let mut counts = [0u32; 128];
+            for _ in 0..2000 {
+                let ip = net.random_address(&mut rng);
+                assert_eq!(ip & net.mask(), net.network());
+                for (bit, count) in counts.iter_mut().enumerate() {
+                    if ip.0 & (1 << bit) != 0 {
+                        *count += 1;
+                    }
+                }
+            }
+            for (bit, count) in counts.iter().enumerate() {
+                if host_mask & (1 << bit) != 0 {
+                    assert!(*count > 850 && *count < 1150, "bit {} of {}", bit, net);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ipv6_anycast() {
+        let mut rng = SmallRng::seed_from_u64(3);
+        let options = RandomOptions::new().exclude_anycast(true);
+
+        // In a /120, the first address and the last 128 ones are reserved.
+        let net = Ipv6Network::from_str("2001:db8::/120").unwrap();
+        for _ in 0..1000 {
+            let host = net.random_address_with(&options, &mut rng).0 & 0xff;
+            assert!(host > 0 && host < 0x80);
+        }
+
+        // In a /127, nothing is excluded.
+        let net = Ipv6Network::from_str("2001:db8::/127").unwrap();
+        let mut seen = [false; 2];
+        for _ in 0..100 {
+            let ip = net.random_address_with(&options, &mut rng);
+            seen[(ip.0 & 1) as usize] = true;
+        }
+        assert_eq!(seen, [true, true]);
+    }
+
+    #[test]
+    fn test_random_subnet() {
+        let mut rng = SmallRng::seed_from_u64(4);
+        let net = Ipv4Network::from_str("10.0.0.0/8").unwrap();
+        assert_eq!(net.random_subnet(8, &mut rng).unwrap(), net);
+        let subnet = net.random_subnet(32, &mut rng).unwrap();
+        assert_eq!(subnet.network() & net.mask(), net.network());
+
+        let net = Ipv6Network::from_str("2001:db8::/48").unwrap();
+        assert!(net.random_subnet(47, &mut rng).is_err());
+        let subnet = net.random_subnet(128, &mut rng).unwrap();
+        assert_eq!(subnet.network() & net.mask(), net.network());
+    }
+}
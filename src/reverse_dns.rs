@@ -0,0 +1,450 @@
+//! Reverse DNS names for IP addresses and networks: the `in-addr.arpa` domain for IPv4 (see
+//! [RFC1035](https://tools.ietf.org/html/rfc1035#section-3.5)) and the `ip6.arpa` domain for IPv6
+//! (see [RFC3596](https://tools.ietf.org/html/rfc3596#section-2.5)).
+
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use ParseErrorReason::*;
+use {
+    Ipv4Address, Ipv4Network, Ipv6Address, Ipv6Network, ParseError, ParseErrorReason, ParsingFailed,
+};
+
+const IN_ADDR_ARPA: &str = "in-addr.arpa";
+const IP6_ARPA: &str = "ip6.arpa";
+
+impl Ipv4Address {
+    /// Return the name under which the PTR record for this address is stored.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = Ipv4Address::from_str("192.0.2.33").unwrap();
+    /// assert_eq!(ip.to_reverse_dns(), "33.2.0.192.in-addr.arpa");
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_reverse_dns(self) -> String {
+        let octets = self.octets();
+        format!(
+            "{}.{}.{}.{}.{}",
+            octets[3], octets[2], octets[1], octets[0], IN_ADDR_ARPA
+        )
+    }
+
+    /// Parse the name of a PTR record in the `in-addr.arpa` domain. The trailing dot of fully
+    /// qualified names is optional, and the `in-addr.arpa` suffix is case insensitive.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = Ipv4Address::from_str("192.0.2.33").unwrap();
+    /// assert_eq!(Ipv4Address::from_reverse_dns("33.2.0.192.in-addr.arpa").unwrap(), ip);
+    /// assert_eq!(Ipv4Address::from_reverse_dns("33.2.0.192.IN-ADDR.ARPA.").unwrap(), ip);
+    /// assert!(Ipv4Address::from_reverse_dns("2.0.192.in-addr.arpa").is_err());
+    /// # }
+    /// ```
+    pub fn from_reverse_dns(name: &str) -> Result<Ipv4Address, ParsingFailed> {
+        let labels = strip_suffix(name, IN_ADDR_ARPA)
+            .ok_or_else(|| error(name, name.len(), UnexpectedEnd))?;
+        let mut address: u32 = 0;
+        let mut count = 0;
+        for (offset, label) in labels_with_offsets(labels).rev() {
+            let octet = match label.len() {
+                1..=3 if label.bytes().all(|b| b.is_ascii_digit()) => label.parse::<u8>().ok(),
+                _ => None,
+            };
+            count += 1;
+            match octet {
+                Some(octet) if count <= 4 => address = (address << 8) | u32::from(octet),
+                _ => return Err(error(name, offset, InvalidLabel)),
+            }
+        }
+        if count != 4 {
+            return Err(error(name, 0, InvalidLabel));
+        }
+        Ok(Ipv4Address(address))
+    }
+}
+
+impl Ipv4Network {
+    /// Return the names of the reverse DNS zones for this network.
+    ///
+    /// - for prefix lengths that are a multiple of 8, up to `/24`, there is a single zone.
+    /// - for other prefix lengths shorter than `/24`, the network is split into the `/8`, `/16` or
+    ///   `/24` zones it covers.
+    /// - for prefix lengths longer than `/24`, the classless delegation name described in
+    ///   [RFC2317](https://tools.ietf.org/html/rfc2317) is used: `<first address>/<prefix
+    ///   length>`, followed by the `/24` zone.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Network;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let net = Ipv4Network::from_str("192.0.2.0/24").unwrap();
+    /// assert_eq!(net.reverse_dns_zones(), vec!["2.0.192.in-addr.arpa"]);
+    ///
+    /// let net = Ipv4Network::from_str("10.0.0.0/15").unwrap();
+    /// assert_eq!(net.reverse_dns_zones(), vec!["0.10.in-addr.arpa", "1.10.in-addr.arpa"]);
+    ///
+    /// let net = Ipv4Network::from_str("192.0.2.64/26").unwrap();
+    /// assert_eq!(net.reverse_dns_zones(), vec!["64/26.2.0.192.in-addr.arpa"]);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn reverse_dns_zones(&self) -> Vec<String> {
+        let prefixlen = self.mask().prefixlen();
+        let network = self.network();
+        if prefixlen > 24 {
+            let octets = network.octets();
+            return vec![format!(
+                "{}/{}.{}.{}.{}.{}",
+                octets[3], prefixlen, octets[2], octets[1], octets[0], IN_ADDR_ARPA
+            )];
+        }
+
+        // Round the prefix length up to the next octet boundary, and enumerate the zones
+        let nb_octets = prefixlen.div_ceil(8);
+        let nb_zones = 1u32 << (nb_octets * 8 - prefixlen);
+        let step = if nb_octets == 0 {
+            0
+        } else {
+            1 << (32 - nb_octets * 8)
+        };
+        (0..nb_zones)
+            .map(|i| zone_name(network + i * step, nb_octets as usize))
+            .collect()
+    }
+}
+
+impl Ipv6Address {
+    /// Return the name under which the PTR record for this address is stored. Each of the 32
+    /// nibbles of the address is a label.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let ip = Ipv6Address::from_str("2001:db8::567:89ab").unwrap();
+    /// assert_eq!(
+    ///     ip.to_reverse_dns(),
+    ///     "b.a.9.8.7.6.5.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"
+    /// );
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_reverse_dns(&self) -> String {
+        nibble_zone_name(*self, 32)
+    }
+
+    /// Parse the name of a PTR record in the `ip6.arpa` domain. The trailing dot of fully
+    /// qualified names is optional, and the name is case insensitive.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Address;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let name = "b.a.9.8.7.6.5.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa.";
+    /// assert_eq!(
+    ///     Ipv6Address::from_reverse_dns(name).unwrap(),
+    ///     Ipv6Address::from_str("2001:db8::567:89ab").unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub fn from_reverse_dns(name: &str) -> Result<Ipv6Address, ParsingFailed> {
+        let labels =
+            strip_suffix(name, IP6_ARPA).ok_or_else(|| error(name, name.len(), UnexpectedEnd))?;
+        let mut address: u128 = 0;
+        let mut count = 0;
+        for (offset, label) in labels_with_offsets(labels).rev() {
+            let nibble = match label.as_bytes() {
+                [b] if b.is_ascii_hexdigit() => (*b as char).to_digit(16).unwrap(),
+                _ => return Err(error(name, offset, InvalidLabel)),
+            };
+            count += 1;
+            if count > 32 {
+                return Err(error(name, offset, InvalidLabel));
+            }
+            address = (address << 4) | u128::from(nibble);
+        }
+        if count != 32 {
+            return Err(error(name, 0, InvalidLabel));
+        }
+        Ok(Ipv6Address(address))
+    }
+}
+
+impl Ipv6Network {
+    /// Return the names of the reverse DNS zones for this network. Zones are delegated on nibble
+    /// boundaries, so if the prefix length is not a multiple of 4, the network is split into the
+    /// zones it covers.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Network;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let net = Ipv6Network::from_str("2001:db8::/32").unwrap();
+    /// assert_eq!(net.reverse_dns_zones(), vec!["8.b.d.0.1.0.0.2.ip6.arpa"]);
+    ///
+    /// let net = Ipv6Network::from_str("2001:db8::/31").unwrap();
+    /// assert_eq!(
+    ///     net.reverse_dns_zones(),
+    ///     vec!["8.b.d.0.1.0.0.2.ip6.arpa", "9.b.d.0.1.0.0.2.ip6.arpa"]
+    /// );
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn reverse_dns_zones(&self) -> Vec<String> {
+        let prefixlen = self.mask().prefixlen();
+        let network = self.network();
+
+        // Round the prefix length up to the next nibble boundary, and enumerate the zones
+        let nb_nibbles = prefixlen.div_ceil(4);
+        let nb_zones = 1u32 << (nb_nibbles * 4 - prefixlen);
+        let step: u128 = if nb_nibbles == 0 {
+            0
+        } else {
+            1 << (128 - nb_nibbles * 4)
+        };
+        (0..nb_zones)
+            .map(|i| nibble_zone_name(network + u128::from(i) * step, nb_nibbles as usize))
+            .collect()
+    }
+}
+
+/// Return the name of the zone that contains the `nb_nibbles` first nibbles of `ip`.
+#[cfg(feature = "alloc")]
+fn nibble_zone_name(ip: Ipv6Address, nb_nibbles: usize) -> String {
+    let mut name = String::with_capacity(nb_nibbles * 2 + IP6_ARPA.len());
+    for i in (0..nb_nibbles).rev() {
+        let nibble = (ip.value() >> ((31 - i) * 4)) & 0xf;
+        name.push(::core::char::from_digit(nibble as u32, 16).unwrap());
+        name.push('.');
+    }
+    name.push_str(IP6_ARPA);
+    name
+}
+
+/// Return the name of the zone that contains the `nb_octets` first octets of `ip`.
+#[cfg(feature = "alloc")]
+fn zone_name(ip: Ipv4Address, nb_octets: usize) -> String {
+    let mut name = String::with_capacity(28);
+    for octet in ip.octets()[..nb_octets].iter().rev() {
+        name.push_str(&octet.to_string());
+        name.push('.');
+    }
+    name.push_str(IN_ADDR_ARPA);
+    name
+}
+
+/// Build the error returned when a reverse DNS name cannot be parsed.
+fn error(name: &str, offset: usize, reason: ParseErrorReason) -> ParsingFailed {
+    ParsingFailed::new(name, ParseError::new(offset, reason))
+}
+
+/// Iterate over the labels of a domain name, along with their offset in the name.
+fn labels_with_offsets(labels: &str) -> impl DoubleEndedIterator<Item = (usize, &str)> {
+    let start = labels.as_ptr() as usize;
+    labels
+        .split('.')
+        .map(move |label| (label.as_ptr() as usize - start, label))
+}
+
+/// Remove the given suffix, as well as the dot that precedes it and the optional trailing dot,
+/// from a name. Return `None` if the name does not end with this suffix, or if no label precedes
+/// it.
+fn strip_suffix<'a>(name: &'a str, suffix: &str) -> Option<&'a str> {
+    let name = if name.ends_with('.') {
+        &name.as_bytes()[..name.len() - 1]
+    } else {
+        name.as_bytes()
+    };
+    // +1 for the dot that separates the suffix from the labels
+    if name.len() <= suffix.len() + 1 {
+        return None;
+    }
+    let (labels, end) = name.split_at(name.len() - suffix.len() - 1);
+    if end[0] == b'.' && end[1..].eq_ignore_ascii_case(suffix.as_bytes()) {
+        // We only split the name before an ASCII dot, so this cannot fail
+        ::core::str::from_utf8(labels).ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    #[test]
+    fn test_to_reverse_dns() {
+        assert_eq!(Ipv4Address(0).to_reverse_dns(), "0.0.0.0.in-addr.arpa");
+        assert_eq!(
+            Ipv4Address(0xffff_fffe).to_reverse_dns(),
+            "254.255.255.255.in-addr.arpa"
+        );
+    }
+
+    #[test]
+    fn test_from_reverse_dns() {
+        let ip = Ipv4Address(0xc000_0221);
+        assert_eq!(
+            Ipv4Address::from_reverse_dns(&ip.to_reverse_dns()).unwrap(),
+            ip
+        );
+        assert_eq!(
+            Ipv4Address::from_reverse_dns("33.2.0.192.In-Addr.Arpa.").unwrap(),
+            ip
+        );
+
+        assert!(Ipv4Address::from_reverse_dns("").is_err());
+        assert!(Ipv4Address::from_reverse_dns("in-addr.arpa").is_err());
+        assert!(Ipv4Address::from_reverse_dns(".in-addr.arpa").is_err());
+        assert!(Ipv4Address::from_reverse_dns("33.2.0.192").is_err());
+        assert!(Ipv4Address::from_reverse_dns("33.2.0.192.ip6.arpa").is_err());
+        assert!(Ipv4Address::from_reverse_dns("33.2.0.192xin-addr.arpa").is_err());
+        assert!(Ipv4Address::from_reverse_dns("1.33.2.0.192.in-addr.arpa").is_err());
+        assert!(Ipv4Address::from_reverse_dns("256.2.0.192.in-addr.arpa").is_err());
+        assert!(Ipv4Address::from_reverse_dns("33.2.0.192.in-addr.arpa..").is_err());
+        assert!(Ipv4Address::from_reverse_dns("é3.2.0.192.in-addr.arpa").is_err());
+    }
+
+    fn zones(s: &str) -> Vec<String> {
+        Ipv4Network::from_str(s).unwrap().reverse_dns_zones()
+    }
+
+    #[test]
+    fn test_reverse_dns_zones() {
+        assert_eq!(zones("0.0.0.0/0"), vec!["in-addr.arpa"]);
+        assert_eq!(zones("10.1.2.3/8"), vec!["10.in-addr.arpa"]);
+        assert_eq!(zones("10.1.2.3/16"), vec!["1.10.in-addr.arpa"]);
+        assert_eq!(zones("10.1.2.3/24"), vec!["2.1.10.in-addr.arpa"]);
+        assert_eq!(
+            zones("10.1.2.3/22"),
+            vec![
+                "0.1.10.in-addr.arpa",
+                "1.1.10.in-addr.arpa",
+                "2.1.10.in-addr.arpa",
+                "3.1.10.in-addr.arpa",
+            ]
+        );
+        assert_eq!(zones("10.1.2.3/7").len(), 2);
+        assert_eq!(zones("10.1.2.3/1").len(), 128);
+        assert_eq!(zones("10.1.2.3/25"), vec!["0/25.2.1.10.in-addr.arpa"]);
+        assert_eq!(zones("10.1.2.131/25"), vec!["128/25.2.1.10.in-addr.arpa"]);
+        assert_eq!(zones("10.1.2.3/32"), vec!["3/32.2.1.10.in-addr.arpa"]);
+    }
+
+    #[test]
+    fn test_ipv6_to_reverse_dns() {
+        assert_eq!(
+            Ipv6Address(1).to_reverse_dns(),
+            "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.ip6.arpa"
+        );
+        assert_eq!(
+            Ipv6Address(0xfe80_0000_0000_0000_8657_e6fe_08d5_5325).to_reverse_dns(),
+            "5.2.3.5.5.d.8.0.e.f.6.e.7.5.6.8.0.0.0.0.0.0.0.0.0.0.0.0.0.8.e.f.ip6.arpa"
+        );
+    }
+
+    #[test]
+    fn test_ipv6_from_reverse_dns() {
+        let ip = Ipv6Address(0xfe80_0000_0000_0000_8657_e6fe_08d5_5325);
+        assert_eq!(
+            Ipv6Address::from_reverse_dns(&ip.to_reverse_dns()).unwrap(),
+            ip
+        );
+        assert_eq!(
+            Ipv6Address::from_reverse_dns(
+                "5.2.3.5.5.D.8.0.E.F.6.E.7.5.6.8.0.0.0.0.0.0.0.0.0.0.0.0.0.8.E.F.IP6.ARPA."
+            )
+            .unwrap(),
+            ip
+        );
+
+        // too short
+        assert!(Ipv6Address::from_reverse_dns(
+            "2.3.5.5.d.8.0.e.f.6.e.7.5.6.8.0.0.0.0.0.0.0.0.0.0.0.0.0.8.e.f.ip6.arpa"
+        )
+        .is_err());
+        // too long
+        assert!(Ipv6Address::from_reverse_dns(
+            "0.5.2.3.5.5.d.8.0.e.f.6.e.7.5.6.8.0.0.0.0.0.0.0.0.0.0.0.0.0.8.e.f.ip6.arpa"
+        )
+        .is_err());
+        // labels with more than one nibble
+        assert!(Ipv6Address::from_reverse_dns(
+            "25.3.5.5.d.8.0.e.f.6.e.7.5.6.8.0.0.0.0.0.0.0.0.0.0.0.0.0.8.e.f.ip6.arpa"
+        )
+        .is_err());
+        // empty label
+        assert!(Ipv6Address::from_reverse_dns(
+            "5.2.3.5.5.d.8.0.e.f.6.e.7.5.6.8.0.0.0.0.0.0.0.0.0.0.0.0..8.e.f.ip6.arpa"
+        )
+        .is_err());
+        // not a hex digit
+        assert!(Ipv6Address::from_reverse_dns(
+            "5.2.3.5.5.d.8.0.e.f.6.e.7.5.6.8.0.0.0.0.0.0.0.0.0.0.0.0.0.8.e.g.ip6.arpa"
+        )
+        .is_err());
+        assert!(Ipv6Address::from_reverse_dns(&ip.to_reverse_dns().replace("ip6", "ip7")).is_err());
+        assert!(Ipv6Address::from_reverse_dns("ip6.arpa").is_err());
+    }
+
+    fn ipv6_zones(s: &str) -> Vec<String> {
+        Ipv6Network::from_str(s).unwrap().reverse_dns_zones()
+    }
+
+    #[test]
+    fn test_ipv6_reverse_dns_zones() {
+        assert_eq!(ipv6_zones("::/0"), vec!["ip6.arpa"]);
+        assert_eq!(
+            ipv6_zones("2001:db8:1234::/48"),
+            vec!["4.3.2.1.8.b.d.0.1.0.0.2.ip6.arpa"]
+        );
+        assert_eq!(
+            ipv6_zones("2001:db8:1234::/46"),
+            vec![
+                "4.3.2.1.8.b.d.0.1.0.0.2.ip6.arpa",
+                "5.3.2.1.8.b.d.0.1.0.0.2.ip6.arpa",
+                "6.3.2.1.8.b.d.0.1.0.0.2.ip6.arpa",
+                "7.3.2.1.8.b.d.0.1.0.0.2.ip6.arpa",
+            ]
+        );
+        assert_eq!(
+            ipv6_zones("2001:db8::/1"),
+            vec![
+                "0.ip6.arpa",
+                "1.ip6.arpa",
+                "2.ip6.arpa",
+                "3.ip6.arpa",
+                "4.ip6.arpa",
+                "5.ip6.arpa",
+                "6.ip6.arpa",
+                "7.ip6.arpa"
+            ]
+        );
+        assert_eq!(ipv6_zones("::1/128"), vec![Ipv6Address(1).to_reverse_dns()]);
+    }
+
+    #[test]
+    fn test_from_reverse_dns_error_details() {
+        let error = |name| {
+            let err = Ipv4Address::from_reverse_dns(name).unwrap_err();
+            (err.offset(), err.reason())
+        };
+        assert_eq!(error("33.2.0.192.example.com"), (22, UnexpectedEnd));
+        assert_eq!(error("33.2.256.192.in-addr.arpa"), (5, InvalidLabel));
+        assert_eq!(error("33..0.192.in-addr.arpa"), (3, InvalidLabel));
+        assert_eq!(error("1.33.2.0.192.in-addr.arpa"), (0, InvalidLabel));
+
+        let name = "b.a.9.8.7.6.5.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.x.ip6.arpa";
+        let err = Ipv6Address::from_reverse_dns(name).unwrap_err();
+        assert_eq!((err.offset(), err.reason()), (62, InvalidLabel));
+    }
+}
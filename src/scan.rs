@@ -0,0 +1,354 @@
+//! Extraction of the IP addresses, networks and ranges that appear in free-form text.
+
+use {Ipv4Address, Ipv4Mask, Ipv4Network, Ipv6Address, Ipv6Mask, Ipv6Network, ParseOptions};
+
+/// A value found by a [`Scanner`](struct.Scanner.html).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ScanValue {
+    /// An IPv4 address, like `192.0.2.33`
+    Ipv4Address(Ipv4Address),
+    /// An IPv6 address, like `2001:db8::1`
+    Ipv6Address(Ipv6Address),
+    /// An IPv4 network, like `192.0.2.0/24`
+    Ipv4Network(Ipv4Network),
+    /// An IPv6 network, like `2001:db8::/32`
+    Ipv6Network(Ipv6Network),
+    /// An inclusive range of IPv4 addresses, like `192.0.2.1-192.0.2.9`
+    Ipv4Range(Ipv4Address, Ipv4Address),
+    /// An inclusive range of IPv6 addresses, like `2001:db8::1-2001:db8::9`
+    Ipv6Range(Ipv6Address, Ipv6Address),
+}
+
+/// A match returned by a [`Scanner`](struct.Scanner.html): the parsed value, and the position of
+/// the text it was parsed from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ScanMatch {
+    start: usize,
+    end: usize,
+    value: ScanValue,
+}
+
+impl ScanMatch {
+    /// Return the offset, in bytes, of the beginning of the match.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Return the offset, in bytes, of the end of the match. The match does not include the byte
+    /// at this offset.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Return the parsed value.
+    pub fn value(&self) -> ScanValue {
+        self.value
+    }
+}
+
+/// An iterator over the IP addresses, networks and ranges that appear in a text. Networks are
+/// expected in the CIDR notation (`192.0.2.0/24`), and ranges as two addresses of the same family
+/// separated by a dash, optionally surrounded by spaces (`192.0.2.1 - 192.0.2.9`).
+///
+/// Values must be delimited by characters that cannot be part of an address: `1.2.3.4.5` and
+/// `1:2:3:4:5:6:7:8:9` are not matched at all, but the trailing punctuation in `1.2.3.4.` or
+/// `fe80::1:` is ignored, and so are brackets, ports and zone identifiers, like in
+/// `[fe80::1%eth0]:443`.
+///
+/// ```rust
+/// # use ipaddr::{Ipv4Address, Ipv4Network, Ipv6Address, ScanValue, Scanner};
+/// # use std::str::FromStr;
+/// # fn main() {
+/// let text = "Blocked 192.0.2.0/24 and [2001:db8::1]:443, but not 1.2.3.4.5.";
+/// let matches: Vec<_> = Scanner::new(text.as_bytes()).collect();
+/// assert_eq!(matches.len(), 2);
+///
+/// assert_eq!(&text[matches[0].start()..matches[0].end()], "192.0.2.0/24");
+/// let network = Ipv4Network::from_str("192.0.2.0/24").unwrap();
+/// assert_eq!(matches[0].value(), ScanValue::Ipv4Network(network));
+///
+/// assert_eq!(&text[matches[1].start()..matches[1].end()], "2001:db8::1");
+/// let ip = Ipv6Address::from_str("2001:db8::1").unwrap();
+/// assert_eq!(matches[1].value(), ScanValue::Ipv6Address(ip));
+/// # }
+/// ```
+pub struct Scanner<'a> {
+    text: &'a [u8],
+    offset: usize,
+    options: ParseOptions,
+}
+
+impl<'a> Scanner<'a> {
+    /// Create a scanner for the given text.
+    pub fn new(text: &'a [u8]) -> Self {
+        Scanner {
+            text,
+            offset: 0,
+            options: ParseOptions::new(),
+        }
+    }
+
+    /// Return `true` if a value can start at the given offset, i.e. if it's not in the middle of
+    /// a word or of something that looks like an address.
+    fn is_start(&self, offset: usize) -> bool {
+        let b = self.text[offset];
+        if !(b.is_ascii_hexdigit() || b == b':') {
+            return false;
+        }
+        match offset.checked_sub(1).map(|i| self.text[i]) {
+            Some(prev) => !(prev.is_ascii_alphanumeric() || prev == b'.' || prev == b':'),
+            None => true,
+        }
+    }
+
+    /// Return `true` if a value can end at the given offset, i.e. if the following bytes cannot
+    /// be part of the value. After an IPv4 value, a colon is always accepted since it usually
+    /// introduces a port.
+    fn is_end(&self, offset: usize, ipv6: bool) -> bool {
+        let next = |i| self.text.get(offset + i).cloned();
+        match next(0) {
+            None => true,
+            Some(b) if b.is_ascii_alphanumeric() => false,
+            // A dot followed by a digit means the address goes on, like in 1.2.3.4.5
+            Some(b'.') => !next(1).is_some_and(|b| b.is_ascii_digit()),
+            // Same for a colon followed by something that could be an hextet
+            Some(b':') if ipv6 => !next(1).is_some_and(|b| b.is_ascii_hexdigit() || b == b':'),
+            Some(_) => true,
+        }
+    }
+
+    /// Try to read an address at the given offset.
+    fn read_address(&self, offset: usize) -> Option<(Address, usize)> {
+        let bytes = &self.text[offset..];
+        if let Ok((ip, len)) = Ipv4Address::parse_prefix_with_options(bytes, &self.options) {
+            if self.is_end(offset + len, false) {
+                return Some((Address::V4(Ipv4Address(ip)), offset + len));
+            }
+            return None;
+        }
+        if let Ok((ip, len)) = Ipv6Address::parse_prefix_with_options(bytes, &self.options) {
+            if self.is_end(offset + len, true) {
+                return Some((Address::V6(Ipv6Address(ip)), offset + len));
+            }
+        }
+        None
+    }
+
+    /// Try to read a prefix length, like `/24`, at the given offset.
+    fn read_prefixlen(&self, offset: usize, max: u8, ipv6: bool) -> Option<(u8, usize)> {
+        if self.text.get(offset) != Some(&b'/') {
+            return None;
+        }
+        let digits = self.text[offset + 1..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        let end = offset + 1 + digits;
+        if digits == 0 || digits > 3 || !self.is_end(end, ipv6) {
+            return None;
+        }
+        let prefixlen = self.text[offset + 1..end]
+            .iter()
+            .fold(0, |acc, b| acc * 10 + u16::from(b - b'0'));
+        if prefixlen > u16::from(max) {
+            return None;
+        }
+        Some((prefixlen as u8, end))
+    }
+
+    /// Try to read the end of a range, like `-192.0.2.9` or ` - 192.0.2.9`, at the given offset.
+    fn read_range_end(&self, offset: usize) -> Option<(Address, usize)> {
+        let skip_space = |i: usize| {
+            if self.text.get(i) == Some(&b' ') {
+                i + 1
+            } else {
+                i
+            }
+        };
+        let dash = skip_space(offset);
+        if self.text.get(dash) != Some(&b'-') {
+            return None;
+        }
+        let start = skip_space(dash + 1);
+        if start >= self.text.len() || !self.is_start(start) {
+            return None;
+        }
+        self.read_address(start)
+    }
+
+    /// Try to read a value at the given offset.
+    fn read(&self, offset: usize) -> Option<ScanMatch> {
+        let (address, end) = self.read_address(offset)?;
+        let (value, end) = match address {
+            Address::V4(ip) => {
+                if let Some((prefixlen, end)) = self.read_prefixlen(end, 32, false) {
+                    let mask = Ipv4Mask::from_prefixlen(prefixlen).unwrap();
+                    (ScanValue::Ipv4Network(Ipv4Network::new(ip, mask)), end)
+                } else {
+                    match self.read_range_end(end) {
+                        Some((Address::V4(last), end)) if last.value() >= ip.value() => {
+                            (ScanValue::Ipv4Range(ip, last), end)
+                        }
+                        _ => (ScanValue::Ipv4Address(ip), end),
+                    }
+                }
+            }
+            Address::V6(ip) => {
+                if let Some((prefixlen, end)) = self.read_prefixlen(end, 128, true) {
+                    let mask = Ipv6Mask::from_prefixlen(prefixlen).unwrap();
+                    (ScanValue::Ipv6Network(Ipv6Network::new(ip, mask)), end)
+                } else if !self.text[offset..end].iter().any(u8::is_ascii_hexdigit) {
+                    // Don't match a lonely "::", unless it's part of a network like "::/0"
+                    return None;
+                } else {
+                    match self.read_range_end(end) {
+                        Some((Address::V6(last), end)) if last.value() >= ip.value() => {
+                            (ScanValue::Ipv6Range(ip, last), end)
+                        }
+                        _ => (ScanValue::Ipv6Address(ip), end),
+                    }
+                }
+            }
+        };
+        Some(ScanMatch {
+            start: offset,
+            end,
+            value,
+        })
+    }
+}
+
+impl<'a> Iterator for Scanner<'a> {
+    type Item = ScanMatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.offset < self.text.len() {
+            let offset = self.offset;
+            if self.is_start(offset) {
+                if let Some(m) = self.read(offset) {
+                    self.offset = m.end;
+                    return Some(m);
+                }
+            }
+            self.offset += 1;
+        }
+        None
+    }
+}
+
+/// An address read by the scanner, before we know whether it's part of a network or a range.
+enum Address {
+    V4(Ipv4Address),
+    V6(Ipv6Address),
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    fn scan(text: &str) -> Vec<(&str, ScanValue)> {
+        Scanner::new(text.as_bytes())
+            .map(|m| (&text[m.start()..m.end()], m.value()))
+            .collect()
+    }
+
+    fn v4(s: &str) -> Ipv4Address {
+        Ipv4Address::from_str(s).unwrap()
+    }
+
+    fn v6(s: &str) -> Ipv6Address {
+        Ipv6Address::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_addresses() {
+        let text = "from 192.0.2.33 to 2001:db8::1, then ::ffff:1.2.3.4 and 10.0.0.1.";
+        assert_eq!(
+            scan(text),
+            vec![
+                ("192.0.2.33", ScanValue::Ipv4Address(v4("192.0.2.33"))),
+                ("2001:db8::1", ScanValue::Ipv6Address(v6("2001:db8::1"))),
+                (
+                    "::ffff:1.2.3.4",
+                    ScanValue::Ipv6Address(v6("::ffff:1.2.3.4"))
+                ),
+                ("10.0.0.1", ScanValue::Ipv4Address(v4("10.0.0.1"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_boundaries() {
+        assert!(scan("1.2.3.4.5").is_empty());
+        assert!(scan("a1.2.3.4").is_empty());
+        assert!(scan("1.2.3.4a").is_empty());
+        assert!(scan("1.2.3.256").is_empty());
+        assert!(scan("1:2:3:4:5:6:7:8:9").is_empty());
+        assert!(scan("std::vector and :: and 12:30").is_empty());
+        assert!(scan("fe80::1::2").is_empty());
+        assert!(scan("version 1.2.3").is_empty());
+
+        let only = |text| {
+            let matches = scan(text);
+            assert_eq!(matches.len(), 1, "{}", text);
+            matches[0].0
+        };
+        assert_eq!(only("(1.2.3.4)"), "1.2.3.4");
+        assert_eq!(only("1.2.3.4:8080"), "1.2.3.4");
+        assert_eq!(only("\"1.2.3.4\","), "1.2.3.4");
+        assert_eq!(only("fe80::1:"), "fe80::1");
+        assert_eq!(only("fe80::1: error"), "fe80::1");
+        assert_eq!(only("[fe80::1]:443"), "fe80::1");
+        assert_eq!(only("fe80::1%eth0"), "fe80::1");
+        assert_eq!(only("http://[2001:db8::1]/index.html"), "2001:db8::1");
+        assert_eq!(only("1.2.3.4/"), "1.2.3.4");
+        assert_eq!(only("1.2.3.4/33"), "1.2.3.4");
+        assert_eq!(only("1.2.3.4/24x"), "1.2.3.4");
+    }
+
+    #[test]
+    fn test_networks() {
+        let net4 = |s| ScanValue::Ipv4Network(Ipv4Network::from_str(s).unwrap());
+        let net6 = |s| ScanValue::Ipv6Network(Ipv6Network::from_str(s).unwrap());
+        assert_eq!(
+            scan("block 192.0.2.0/24, 2001:db8::/32 and ::/0."),
+            vec![
+                ("192.0.2.0/24", net4("192.0.2.0/24")),
+                ("2001:db8::/32", net6("2001:db8::/32")),
+                ("::/0", net6("::/0")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ranges() {
+        assert_eq!(
+            scan("10.0.0.1-10.0.0.9; 10.0.1.1 - 10.0.1.9; fe80::1-fe80::2"),
+            vec![
+                (
+                    "10.0.0.1-10.0.0.9",
+                    ScanValue::Ipv4Range(v4("10.0.0.1"), v4("10.0.0.9"))
+                ),
+                (
+                    "10.0.1.1 - 10.0.1.9",
+                    ScanValue::Ipv4Range(v4("10.0.1.1"), v4("10.0.1.9"))
+                ),
+                (
+                    "fe80::1-fe80::2",
+                    ScanValue::Ipv6Range(v6("fe80::1"), v6("fe80::2"))
+                ),
+            ]
+        );
+        // reversed ranges and mixed families are not ranges
+        assert_eq!(
+            scan("10.0.0.9-10.0.0.1 10.0.0.1-::1"),
+            vec![
+                ("10.0.0.9", ScanValue::Ipv4Address(v4("10.0.0.9"))),
+                ("10.0.0.1", ScanValue::Ipv4Address(v4("10.0.0.1"))),
+                ("10.0.0.1", ScanValue::Ipv4Address(v4("10.0.0.1"))),
+                ("::1", ScanValue::Ipv6Address(v6("::1"))),
+            ]
+        );
+    }
+}
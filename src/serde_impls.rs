@@ -0,0 +1,347 @@
+//! `Serialize` and `Deserialize` implementations, enabled by the `serde` feature.
+//!
+//! Human readable formats (JSON, YAML, TOML...) use the same text representation as `Display`
+//! and `FromStr`. Binary formats use a compact representation:
+//!
+//! - addresses and masks are tuples of bytes in network byte order (4 for IPv4, 16 for IPv6)
+//! - networks are a tuple of an address and a prefix length
+//!
+//! Masks are validated when they are deserialized, just like with `TryFrom`.
+
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+use core::marker::PhantomData;
+use core::str::FromStr;
+
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Error, Serialize, SerializeTuple, Serializer};
+
+use {Ipv4Address, Ipv4Mask, Ipv4Network, Ipv6Address, Ipv6Mask, Ipv6Network, StrBuf};
+
+/// Large enough for any IPv6 network, like `ffff:ffff:ffff:ffff:ffff:ffff:255.255.255.255/128`.
+type NetworkStrBuf = StrBuf<49>;
+
+fn serialize_octets<S: Serializer>(octets: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(octets.len())?;
+    for octet in octets {
+        tuple.serialize_element(octet)?;
+    }
+    tuple.end()
+}
+
+fn serialize_network<S, T>(ip: T, prefixlen: u32, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: fmt::Display + Serialize,
+{
+    if serializer.is_human_readable() {
+        let mut buf = NetworkStrBuf::new();
+        write!(buf, "{}/{}", ip, prefixlen).map_err(S::Error::custom)?;
+        serializer.serialize_str(&buf)
+    } else {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&ip)?;
+        tuple.serialize_element(&(prefixlen as u8))?;
+        tuple.end()
+    }
+}
+
+/// Deserialize the human readable representation of a `T` with its `FromStr` implementation.
+struct TextVisitor<T> {
+    expecting: &'static str,
+    marker: PhantomData<T>,
+}
+
+impl<T> TextVisitor<T> {
+    fn new(expecting: &'static str) -> Self {
+        TextVisitor {
+            expecting,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T> Visitor<'de> for TextVisitor<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<T, E> {
+        T::from_str(s).map_err(E::custom)
+    }
+}
+
+/// Deserialize a tuple of `N` bytes.
+struct OctetsVisitor<const N: usize>;
+
+impl<'de, const N: usize> Visitor<'de> for OctetsVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} bytes", N)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<[u8; N], A::Error> {
+        let mut octets = [0; N];
+        for (i, octet) in octets.iter_mut().enumerate() {
+            *octet = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        Ok(octets)
+    }
+}
+
+/// Deserialize a tuple of an address and a prefix length.
+struct NetworkVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for NetworkVisitor<T> {
+    type Value = (T, u8);
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an address and a prefix length")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(T, u8), A::Error> {
+        let ip = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let prefixlen = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok((ip, prefixlen))
+    }
+}
+
+fn deserialize_octets<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_tuple(N, OctetsVisitor::<N>)
+}
+
+fn deserialize_network<'de, D, T>(deserializer: D) -> Result<(T, u8), D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    deserializer.deserialize_tuple(2, NetworkVisitor(PhantomData))
+}
+
+impl Serialize for Ipv4Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_str_buf())
+        } else {
+            serialize_octets(&self.octets(), serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Ipv4Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(TextVisitor::new("an IPv4 address"))
+        } else {
+            deserialize_octets::<D, 4>(deserializer)
+                .map(|octets| Ipv4Address::from_slice_unchecked(&octets))
+        }
+    }
+}
+
+impl Serialize for Ipv6Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_str_buf())
+        } else {
+            serialize_octets(&self.octets(), serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Ipv6Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(TextVisitor::new("an IPv6 address"))
+        } else {
+            deserialize_octets::<D, 16>(deserializer).map(Ipv6Address::from)
+        }
+    }
+}
+
+impl Serialize for Ipv4Mask {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Ipv4Address::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ipv4Mask {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(TextVisitor::new("an IPv4 mask"))
+        } else {
+            let ip = Ipv4Address::deserialize(deserializer)?;
+            Ipv4Mask::try_from(ip).map_err(de::Error::custom)
+        }
+    }
+}
+
+impl Serialize for Ipv6Mask {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Ipv6Address::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ipv6Mask {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(TextVisitor::new("an IPv6 mask"))
+        } else {
+            let ip = Ipv6Address::deserialize(deserializer)?;
+            Ipv6Mask::try_from(ip).map_err(de::Error::custom)
+        }
+    }
+}
+
+impl Serialize for Ipv4Network {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_network(self.0, self.1.prefixlen(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ipv4Network {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(TextVisitor::new("an IPv4 network"))
+        } else {
+            let (ip, prefixlen) = deserialize_network(deserializer)?;
+            let mask = Ipv4Mask::from_prefixlen(prefixlen).map_err(de::Error::custom)?;
+            Ok(Ipv4Network(ip, mask))
+        }
+    }
+}
+
+impl Serialize for Ipv6Network {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_network(self.0, self.1.prefixlen(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ipv6Network {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(TextVisitor::new("an IPv6 network"))
+        } else {
+            let (ip, prefixlen) = deserialize_network(deserializer)?;
+            let mask = Ipv6Mask::from_prefixlen(prefixlen).map_err(de::Error::custom)?;
+            Ok(Ipv6Network(ip, mask))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use serde_test::{assert_de_tokens_error, assert_tokens, Compact, Configure, Readable, Token};
+
+    #[test]
+    fn test_ipv4_address() {
+        let ip = Ipv4Address(0xc000_0221);
+        assert_tokens(&ip.readable(), &[Token::Str("192.0.2.33")]);
+        assert_tokens(
+            &ip.compact(),
+            &[
+                Token::Tuple { len: 4 },
+                Token::U8(192),
+                Token::U8(0),
+                Token::U8(2),
+                Token::U8(33),
+                Token::TupleEnd,
+            ],
+        );
+        assert_de_tokens_error::<Readable<Ipv4Address>>(
+            &[Token::Str("192.0.2")],
+            "malformed address: \"192.0.2\": unexpected end of string at offset 7",
+        );
+    }
+
+    #[test]
+    fn test_ipv6_address() {
+        let ip = Ipv6Address(0xfe80_0000_0000_0000_0000_0000_0000_0001);
+        assert_tokens(&ip.readable(), &[Token::Str("fe80::1")]);
+        let mut tokens = vec![Token::Tuple { len: 16 }, Token::U8(0xfe), Token::U8(0x80)];
+        tokens.extend(vec![Token::U8(0); 13]);
+        tokens.extend(vec![Token::U8(1), Token::TupleEnd]);
+        assert_tokens(&ip.compact(), &tokens);
+    }
+
+    #[test]
+    fn test_masks() {
+        let mask = Ipv4Mask::from_prefixlen(20).unwrap();
+        assert_tokens(&mask.readable(), &[Token::Str("255.255.240.0")]);
+        let octets = |a, b, c, d| {
+            vec![
+                Token::Tuple { len: 4 },
+                Token::U8(a),
+                Token::U8(b),
+                Token::U8(c),
+                Token::U8(d),
+                Token::TupleEnd,
+            ]
+        };
+        assert_tokens(&mask.compact(), &octets(255, 255, 240, 0));
+        assert_de_tokens_error::<Compact<Ipv4Mask>>(&octets(255, 0, 255, 0), "invalid mask");
+
+        let mask = Ipv6Mask::from_prefixlen(16).unwrap();
+        assert_tokens(&mask.readable(), &[Token::Str("ffff::")]);
+        let mut tokens = vec![
+            Token::Tuple { len: 16 },
+            Token::U8(0xff),
+            Token::U8(0),
+            Token::U8(0xff),
+        ];
+        tokens.extend(vec![Token::U8(0); 13]);
+        tokens.push(Token::TupleEnd);
+        assert_de_tokens_error::<Compact<Ipv6Mask>>(&tokens, "invalid mask");
+    }
+
+    #[test]
+    fn test_networks() {
+        let net = Ipv4Network::from_str("192.0.2.1/24").unwrap();
+        assert_tokens(&net.readable(), &[Token::Str("192.0.2.1/24")]);
+        assert_tokens(
+            &net.compact(),
+            &[
+                Token::Tuple { len: 2 },
+                Token::Tuple { len: 4 },
+                Token::U8(192),
+                Token::U8(0),
+                Token::U8(2),
+                Token::U8(1),
+                Token::TupleEnd,
+                Token::U8(24),
+                Token::TupleEnd,
+            ],
+        );
+
+        let net = Ipv6Network::from_str("2001:db8::/32").unwrap();
+        assert_tokens(&net.readable(), &[Token::Str("2001:db8::/32")]);
+        let mut tokens = vec![
+            Token::Tuple { len: 2 },
+            Token::Tuple { len: 16 },
+            Token::U8(0x20),
+            Token::U8(0x01),
+            Token::U8(0x0d),
+            Token::U8(0xb8),
+        ];
+        tokens.extend(vec![Token::U8(0); 12]);
+        tokens.extend(vec![Token::TupleEnd, Token::U8(129), Token::TupleEnd]);
+        assert_de_tokens_error::<Compact<Ipv6Network>>(&tokens, "invalid mask");
+    }
+}
@@ -0,0 +1,131 @@
+//! Fixed-capacity string buffers, to format addresses without allocating.
+
+use core::fmt;
+use core::ops::Deref;
+use core::str;
+
+/// A string stored inline, that can hold up to `N` bytes. This is returned by the `to_str_buf()`
+/// methods, so that addresses can be formatted without allocating on the heap.
+///
+/// ```rust
+/// # use ipaddr::{Ipv4Address, Ipv4StrBuf};
+/// # use std::str::FromStr;
+/// # fn main() {
+/// let ip = Ipv4Address::from_str("192.0.2.33").unwrap();
+/// let buf: Ipv4StrBuf = ip.to_str_buf();
+/// assert_eq!(buf.as_str(), "192.0.2.33");
+/// assert_eq!(buf.len(), 10);
+/// # }
+/// ```
+#[derive(Copy, Clone)]
+pub struct StrBuf<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+/// A buffer large enough for any IPv4 address or mask, like `255.255.255.255`.
+pub type Ipv4StrBuf = StrBuf<15>;
+
+/// A buffer large enough for any IPv6 address or mask, including the ones formatted with an IPv4
+/// suffix, like `ffff:ffff:ffff:ffff:ffff:ffff:255.255.255.255`.
+pub type Ipv6StrBuf = StrBuf<45>;
+
+impl<const N: usize> StrBuf<N> {
+    /// Create an empty buffer.
+    pub fn new() -> Self {
+        StrBuf {
+            bytes: [0; N],
+            len: 0,
+        }
+    }
+
+    /// Return the content of the buffer.
+    pub fn as_str(&self) -> &str {
+        // We only ever write complete `str`s in the buffer
+        str::from_utf8(&self.bytes[..self.len]).expect("invalid UTF-8 in StrBuf")
+    }
+
+    /// Return the number of bytes that can be stored in this buffer.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<const N: usize> Default for StrBuf<N> {
+    fn default() -> Self {
+        StrBuf::new()
+    }
+}
+
+impl<const N: usize> fmt::Write for StrBuf<N> {
+    /// Append a string to the buffer. This fails if there is not enough space left, in which case
+    /// the buffer is left unchanged.
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > N {
+            return Err(fmt::Error);
+        }
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+impl<const N: usize> Deref for StrBuf<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for StrBuf<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq for StrBuf<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for StrBuf<N> {}
+
+impl<'a, const N: usize> PartialEq<&'a str> for StrBuf<N> {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> fmt::Display for StrBuf<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> fmt::Debug for StrBuf<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_write() {
+        let mut buf = StrBuf::<4>::new();
+        assert_eq!(buf, "");
+        assert!(buf.write_str("ab").is_ok());
+        assert!(buf.write_str("cd").is_ok());
+        assert_eq!(buf, "abcd");
+        assert!(buf.write_str("e").is_err());
+        assert_eq!(buf, "abcd");
+        assert_eq!(buf.capacity(), 4);
+        assert_eq!(format!("{} {:?}", buf, buf), "abcd \"abcd\"");
+    }
+}
@@ -0,0 +1,368 @@
+//! Fast paths for the most common address syntaxes.
+//!
+//! The complete parsers are `const fn`, so they are limited to byte-at-a-time loops. The functions
+//! here validate several bytes at once with SWAR arithmetic ("SIMD within a register": each byte
+//! of an integer is a lane). They only recognize valid addresses, and return `None` for anything
+//! else, including valid syntaxes they do not handle. The caller then falls back to the complete
+//! parser, which also produces the error. A fast path can thus make parsing faster, but it can
+//! never change its result.
+
+/// `0x0101...01`. Multiplying a byte by this repeats it in every lane.
+const ONES: u64 = u64::MAX / 0xff;
+const HIGH_BITS: u64 = ONES * 0x80;
+const LOW_BITS: u64 = ONES * 0x7f;
+
+/// Set the high bit of the lanes that are zero, and clear every other bit.
+#[inline]
+fn zero_lanes(x: u64) -> u64 {
+    // Adding 0x7f to the 7 low bits sets the high bit if any of them is set, without carrying
+    // into the next lane.
+    !(((x & LOW_BITS) + LOW_BITS) | x | LOW_BITS)
+}
+
+/// Set the high bit of the lanes that contain an ASCII decimal digit, and clear every other bit.
+#[inline]
+fn digit_lanes(x: u64) -> u64 {
+    // The high nibble of a digit is 3, and its low nibble is smaller than 10, ie adding 6 to it
+    // does not carry into the high nibble.
+    let high_nibble = zero_lanes((x & (ONES * 0xf0)) ^ (ONES * 0x30));
+    let too_large = (((x & (ONES * 0x0f)) + ONES * 0x06) & (ONES * 0x10)) << 3;
+    high_nibble & !too_large
+}
+
+/// Set the high bit of the lanes that contain an ASCII hexadecimal letter, lower or upper case,
+/// and clear every other bit.
+#[inline]
+fn hex_letter_lanes(x: u64) -> u64 {
+    // Setting bit 5 turns upper case letters into lower case letters, whose high nibble is 6 and
+    // low nibble is between 1 and 6.
+    let low_nibble = x & (ONES * 0x0f);
+    zero_lanes(((x | (ONES * 0x20)) & (ONES * 0xf0)) ^ (ONES * 0x60))
+        & !(((low_nibble + ONES * 0x09) & (ONES * 0x10)) << 3)
+        & (((low_nibble + ONES * 0x0f) & (ONES * 0x10)) << 3)
+}
+
+/// Gather the high bit of each lane into a bit mask, where bit `i` corresponds to byte `i`.
+#[inline]
+fn movemask(x: u64) -> u32 {
+    // The multiplication moves the bit of each lane to a distinct bit of the most significant
+    // byte, and nothing can carry into it.
+    ((x >> 7).wrapping_mul(0x0102_0408_1020_4080) >> 56) as u32
+}
+
+/// Load between 7 and 15 bytes into two integers, in little endian order. The missing bytes are
+/// zero.
+#[inline]
+fn load(bytes: &[u8]) -> (u64, u64) {
+    // Read the first and last eight (or four) bytes, instead of copying a variable number of
+    // bytes, which is much slower. The overlapping bytes are read twice.
+    let len = bytes.len();
+    if len >= 8 {
+        let mut word = [0; 8];
+        word.copy_from_slice(&bytes[..8]);
+        let first = u64::from_le_bytes(word);
+        word.copy_from_slice(&bytes[len - 8..]);
+        let last = (u64::from_le_bytes(word) >> 8) >> (8 * (15 - len));
+        (first, last)
+    } else {
+        let mut word = [0; 4];
+        word.copy_from_slice(&bytes[..4]);
+        let first = u32::from_le_bytes(word);
+        word.copy_from_slice(&bytes[len - 4..]);
+        let last = u32::from_le_bytes(word);
+        (u64::from(first) | u64::from(last) << (8 * (len - 4)), 0)
+    }
+}
+
+/// Parse an IPv4 address in the dotted-decimal form, `a.b.c.d`, where each octet has between one
+/// and three digits.
+#[inline]
+pub(crate) fn parse_ipv4(bytes: &[u8]) -> Option<u32> {
+    let len = bytes.len();
+    if !(7..=15).contains(&len) {
+        return None;
+    }
+    let (low, high) = load(bytes);
+
+    // Every byte is either a digit or a dot, and there is at least one dot
+    let dot = ONES * u64::from(b'.');
+    let dots = movemask(zero_lanes(low ^ dot)) | movemask(zero_lanes(high ^ dot)) << 8;
+    let digits = movemask(digit_lanes(low)) | movemask(digit_lanes(high)) << 8;
+    if dots | digits != (1 << len) - 1 || dots == 0 {
+        return None;
+    }
+
+    // There are exactly three dots, that split the input into four octets of one to three digits.
+    // Looking for the first and the last dot at the same time shortens the dependency chain.
+    let first = dots.trailing_zeros() as usize;
+    let third = 31 - dots.leading_zeros() as usize;
+    let middle = dots & !(1 << first) & !(1 << third);
+    if middle == 0 || middle & (middle - 1) != 0 {
+        return None;
+    }
+    let second = middle.trailing_zeros() as usize;
+    let lengths = [
+        first,
+        second - first - 1,
+        third - second - 1,
+        len - third - 1,
+    ];
+    let longest = lengths[0].max(lengths[1]).max(lengths[2]).max(lengths[3]);
+    let shortest = lengths[0].min(lengths[1]).min(lengths[2]).min(lengths[3]);
+    if shortest == 0 || longest > 3 {
+        return None;
+    }
+
+    // Each octet is read from the three bytes that end it. The digits that do not belong to the
+    // octet are multiplied by zero, instead of branching on the length.
+    let digit = |i: usize| u32::from(bytes[i] & 0x0f);
+    let octet = |end: usize, len: usize| {
+        digit(end - 1)
+            + 10 * digit(end.saturating_sub(2)) * (len >= 2) as u32
+            + 100 * digit(end.saturating_sub(3)) * (len == 3) as u32
+    };
+    let a = octet(first, lengths[0]);
+    let b = octet(second, lengths[1]);
+    let c = octet(third, lengths[2]);
+    let d = octet(len, lengths[3]);
+    if a | b | c | d > 0xff {
+        return None;
+    }
+    Some(a << 24 | b << 16 | c << 8 | d)
+}
+
+/// Read up to four hexadecimal digits, lower or upper case, at the beginning of `bytes`. Return
+/// the number of digits read and their value.
+#[inline]
+fn read_hextet(bytes: &[u8]) -> (usize, u16) {
+    let x = if bytes.len() >= 4 {
+        let mut word = [0; 4];
+        word.copy_from_slice(&bytes[..4]);
+        u64::from(u32::from_le_bytes(word))
+    } else {
+        // Copying less than four bytes would call `memcpy()`
+        bytes
+            .iter()
+            .rev()
+            .fold(0, |word, byte| word << 8 | u64::from(*byte))
+    };
+    let letters = hex_letter_lanes(x);
+
+    // The padding bytes are zero, which is not a digit, so this never goes past the input.
+    let count = ((!(digit_lanes(x) | letters) & HIGH_BITS).trailing_zeros() / 8) as usize;
+    if count == 0 {
+        return (0, 0);
+    }
+    // The value of a letter is its low nibble plus 9. Then, the shift drops the bytes that
+    // follow the hextet, and aligns the last digit on the fourth byte.
+    let values = ((x & (ONES * 0x0f)) + 9 * (letters >> 7)) << (8 * (4 - count));
+    let [a, b, c, d] = (values as u32).to_le_bytes();
+    let hextet = u16::from(a) << 12 | u16::from(b) << 8 | u16::from(c) << 4 | u16::from(d);
+    (count, hextet)
+}
+
+/// Parse an IPv6 address made of hextets separated by colons, with at most one ellipsis, and that
+/// may end with an IPv4 address in the dotted-decimal form.
+#[inline]
+pub(crate) fn parse_ipv6(bytes: &[u8]) -> Option<u128> {
+    let len = bytes.len();
+    if !(2..=45).contains(&len) {
+        return None;
+    }
+
+    // The hextets are accumulated in `address`, and the ones that follow the ellipsis are moved
+    // to the end once they are all read.
+    let mut address: u128 = 0;
+    let mut count = 0;
+    let mut ellipsis = None;
+    let mut offset = 0;
+    if bytes[0] == b':' && bytes[1] == b':' {
+        if len == 2 {
+            return Some(0);
+        }
+        ellipsis = Some(0);
+        offset = 2;
+    }
+
+    loop {
+        let (read, hextet) = read_hextet(&bytes[offset..]);
+        if read == 0 || count == 8 {
+            return None;
+        }
+        address = address << 16 | u128::from(hextet);
+        count += 1;
+        offset += read;
+        if offset == len {
+            break;
+        }
+        if bytes[offset] == b'.' && count < 8 {
+            // The hextet was actually the first octet of an IPv4 address, that must end the
+            // input, and that stands for the last two hextets.
+            let ipv4 = parse_ipv4(&bytes[offset - read..])?;
+            address = (address >> 16) << 32 | u128::from(ipv4);
+            count += 1;
+            break;
+        }
+        if bytes[offset] != b':' || offset + 1 == len {
+            return None;
+        }
+        offset += 1;
+        if bytes[offset] == b':' {
+            if ellipsis.is_some() {
+                return None;
+            }
+            ellipsis = Some(count);
+            offset += 1;
+            if offset == len {
+                break;
+            }
+        }
+    }
+
+    match ellipsis {
+        Some(index) if count < 8 => {
+            let tail_bits = 16 * (count - index);
+            let tail = address & ((1 << tail_bits) - 1);
+            // Shifting by 128 bits would overflow
+            let head = (address >> tail_bits) << (16 * (7 - index)) << 16;
+            Some(head | tail)
+        }
+        None if count == 8 => Some(address),
+        _ => None,
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+    use {Ipv4Address, Ipv6Address};
+
+    /// Check that the fast path agrees with the complete parser on `input`, and return whether it
+    /// handled it.
+    fn check(input: &[u8]) -> bool {
+        let ipv4 = parse_ipv4(input);
+        if let Some(value) = ipv4 {
+            assert_eq!(Ipv4Address::parse(input).ok(), Some(value), "{:?}", input);
+        }
+        let ipv6 = parse_ipv6(input);
+        if let Some(value) = ipv6 {
+            assert_eq!(Ipv6Address::parse(input).ok(), Some(value), "{:?}", input);
+        }
+        ipv4.is_some() || ipv6.is_some()
+    }
+
+    #[test]
+    fn test_parse_ipv4() {
+        for input in &[
+            "0.0.0.0",
+            "255.255.255.255",
+            "192.0.2.33",
+            "1.22.133.4",
+            "001.002.003.004",
+            "000.000.000.000",
+            "10.0.0.1",
+        ] {
+            assert!(check(input.as_bytes()), "{}", input);
+        }
+        for input in &[
+            "",
+            "1.2.3",
+            "1.2.3.4.",
+            ".1.2.3.4",
+            "1..2.3.4",
+            "1.2.3.4.5",
+            "256.0.0.0",
+            "0.0.0.256",
+            "1.2.3.999",
+            "1000.2.3.4",
+            "1.2.3.0004",
+            "1.2.3.4 ",
+            "1.2.3.4/",
+            "1.2.3.4:",
+            "1.2.3.a",
+            "0x1.2.3.4",
+            "1.2.3.4\0\0\0",
+            "1234567890123456",
+        ] {
+            assert!(!check(input.as_bytes()), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_ipv6() {
+        for input in &[
+            "::",
+            "::1",
+            "1::",
+            "2001:db8::1",
+            "2001:DB8::A:b:C",
+            "fe80::",
+            "1:2:3:4:5:6:7:8",
+            "1:2:3:4:5:6::8",
+            "::2:3:4:5:6:7:8",
+            "1:2:3:4:5:6:7::",
+            "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff",
+            "0000:0000:0000:0000:0000:0000:0000:0000",
+            "::ffff:192.0.2.33",
+            "::1.2.3.4",
+            "64:ff9b::255.255.255.255",
+            "1:2:3:4:5:6:1.2.3.4",
+            "1:2:3:4:5::1.2.3.4",
+        ] {
+            assert!(check(input.as_bytes()), "{}", input);
+        }
+        for input in &[
+            ":",
+            ":1",
+            "1:",
+            ":::",
+            "1:::2",
+            "1::2::3",
+            "1:2:3:4:5:6:7",
+            "1:2:3:4:5:6:7:8:9",
+            "1:2:3:4::5:6:7:8",
+            "12345::",
+            "::g",
+            "::ffff:1.2.3",
+            "::ffff:1.2.3.4.5",
+            "::1:2:3:4:5:6:1.2.3.4",
+            "1:2:3:4:5:6:7:1.2.3.4",
+            "::12345.1.2.3",
+            "::a.1.2.3",
+            "fe80::1%eth0",
+            "[::1]",
+        ] {
+            assert!(!check(input.as_bytes()), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_random_inputs() {
+        // Random strings over the alphabet of addresses, so that a good part of them are valid.
+        const ALPHABET: &[u8] = b"0123456789abcdefABCDEFg.:/ ";
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 0..100_000 {
+            let len = rng.gen_range(0..40);
+            let input: Vec<u8> = (0..len)
+                .map(|_| {
+                    if rng.gen_bool(0.3) {
+                        b"0123456789.:"[rng.gen_range(0..12)]
+                    } else {
+                        ALPHABET[rng.gen_range(0..ALPHABET.len())]
+                    }
+                })
+                .collect();
+            check(&input);
+        }
+
+        // Valid addresses, written by the formatters
+        for _ in 0..10_000 {
+            let ipv4 = Ipv4Address(rng.gen());
+            assert!(check(ipv4.to_string().as_bytes()));
+            let ipv6 = Ipv6Address(rng.gen::<u128>() & !(0xffff << (16 * rng.gen_range(0..8))));
+            assert!(check(ipv6.to_string().as_bytes()));
+        }
+    }
+}
@@ -0,0 +1,415 @@
+//! Binary encoding of addresses and networks, as they appear in packets. Everything is in network
+//! byte order (big endian).
+//!
+//! Networks use the prefix encoding of BGP NLRI (see
+//! [RFC4271](https://tools.ietf.org/html/rfc4271#section-4.3)): a byte for the prefix length,
+//! followed by the minimal number of octets that contain the prefix.
+
+#[cfg(feature = "bytes")]
+use bytes::{Buf, BufMut};
+
+use {
+    BufferTooSmall, Ipv4Address, Ipv4Mask, Ipv4Network, Ipv6Address, Ipv6Mask, Ipv6Network,
+    MalformedAddress, IPV4_MAX_PREFIXLEN, IPV6_MAX_PREFIXLEN,
+};
+
+impl Ipv4Address {
+    /// Read an address from the four first bytes of `bytes`, and return it along with the
+    /// remaining bytes.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Address;
+    /// # fn main() {
+    /// let (ip, rest) = Ipv4Address::read_from(&[192, 0, 2, 33, 0xff]).unwrap();
+    /// assert_eq!(ip, Ipv4Address::from(0xc000_0221));
+    /// assert_eq!(rest, &[0xff]);
+    ///
+    /// assert!(Ipv4Address::read_from(&[192, 0, 2]).is_err());
+    /// # }
+    /// ```
+    pub fn read_from(bytes: &[u8]) -> Result<(Ipv4Address, &[u8]), MalformedAddress> {
+        let (octets, rest) = split(bytes, 4)?;
+        Ok((Ipv4Address::from_slice_unchecked(octets), rest))
+    }
+
+    /// Write the address in the four first bytes of `buf`, and return the remaining bytes.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Address;
+    /// # fn main() {
+    /// let mut buf = [0; 6];
+    /// let rest = Ipv4Address::from(0xc000_0221).write_to(&mut buf[1..]).unwrap();
+    /// assert_eq!(rest.len(), 1);
+    /// assert_eq!(buf, [0, 192, 0, 2, 33, 0]);
+    /// # }
+    /// ```
+    pub fn write_to(self, buf: &mut [u8]) -> Result<&mut [u8], BufferTooSmall> {
+        write(&self.octets(), buf)
+    }
+
+    /// Read an address from a [`Buf`](https://docs.rs/bytes/1/bytes/trait.Buf.html) and advance
+    /// it. This requires the `bytes` feature.
+    #[cfg(feature = "bytes")]
+    pub fn read_from_buf<B: Buf>(buf: &mut B) -> Result<Ipv4Address, MalformedAddress> {
+        let mut octets = [0; 4];
+        read_buf(buf, &mut octets)?;
+        Ok(Ipv4Address::from_slice_unchecked(&octets))
+    }
+
+    /// Write the address in a [`BufMut`](https://docs.rs/bytes/1/bytes/trait.BufMut.html). This
+    /// requires the `bytes` feature.
+    #[cfg(feature = "bytes")]
+    pub fn write_to_buf<B: BufMut>(self, buf: &mut B) -> Result<(), BufferTooSmall> {
+        write_buf(&self.octets(), buf)
+    }
+}
+
+impl Ipv6Address {
+    /// Read an address from the sixteen first bytes of `bytes`, and return it along with the
+    /// remaining bytes.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Address;
+    /// # fn main() {
+    /// let mut bytes = [0; 17];
+    /// bytes[0] = 0xfe;
+    /// bytes[1] = 0x80;
+    /// bytes[15] = 1;
+    /// let (ip, rest) = Ipv6Address::read_from(&bytes).unwrap();
+    /// assert_eq!(ip, Ipv6Address::from(0xfe80_0000_0000_0000_0000_0000_0000_0001));
+    /// assert_eq!(rest, &[0]);
+    /// # }
+    /// ```
+    pub fn read_from(bytes: &[u8]) -> Result<(Ipv6Address, &[u8]), MalformedAddress> {
+        let (octets, rest) = split(bytes, 16)?;
+        Ok((Ipv6Address::from_slice_unchecked(octets), rest))
+    }
+
+    /// Write the address in the sixteen first bytes of `buf`, and return the remaining bytes.
+    pub fn write_to<'a>(&self, buf: &'a mut [u8]) -> Result<&'a mut [u8], BufferTooSmall> {
+        write(&self.octets(), buf)
+    }
+
+    /// Read an address from a [`Buf`](https://docs.rs/bytes/1/bytes/trait.Buf.html) and advance
+    /// it. This requires the `bytes` feature.
+    #[cfg(feature = "bytes")]
+    pub fn read_from_buf<B: Buf>(buf: &mut B) -> Result<Ipv6Address, MalformedAddress> {
+        let mut octets = [0; 16];
+        read_buf(buf, &mut octets)?;
+        Ok(Ipv6Address::from_slice_unchecked(&octets))
+    }
+
+    /// Write the address in a [`BufMut`](https://docs.rs/bytes/1/bytes/trait.BufMut.html). This
+    /// requires the `bytes` feature.
+    #[cfg(feature = "bytes")]
+    pub fn write_to_buf<B: BufMut>(&self, buf: &mut B) -> Result<(), BufferTooSmall> {
+        write_buf(&self.octets(), buf)
+    }
+}
+
+impl Ipv4Network {
+    /// Read a network in the BGP NLRI prefix encoding, and return it along with the remaining
+    /// bytes. The bits of the last octet that are not part of the prefix are ignored.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Network;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let (net, rest) = Ipv4Network::read_prefix(&[20, 10, 1, 0x1f, 42]).unwrap();
+    /// assert_eq!(net, Ipv4Network::from_str("10.1.16.0/20").unwrap());
+    /// assert_eq!(rest, &[42]);
+    ///
+    /// // the prefix length is too big
+    /// assert!(Ipv4Network::read_prefix(&[33, 10, 1, 16, 0, 0]).is_err());
+    /// // three octets are needed for a /20
+    /// assert!(Ipv4Network::read_prefix(&[20, 10, 1]).is_err());
+    /// # }
+    /// ```
+    pub fn read_prefix(bytes: &[u8]) -> Result<(Ipv4Network, &[u8]), MalformedAddress> {
+        let mut octets = [0; 4];
+        let (prefixlen, rest) = read_prefix(bytes, IPV4_MAX_PREFIXLEN, &mut octets)?;
+        let mask = Ipv4Mask::from_prefixlen(prefixlen).map_err(|_| MalformedAddress)?;
+        let ip = Ipv4Address::from_slice_unchecked(&octets) & mask;
+        Ok((Ipv4Network(ip, mask), rest))
+    }
+
+    /// Write the network in the BGP NLRI prefix encoding, and return the remaining bytes. Only
+    /// the network part of the address is written.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv4Network;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let net = Ipv4Network::from_str("10.1.17.1/20").unwrap();
+    /// let mut buf = [0; 8];
+    /// let len = buf.len() - net.write_prefix(&mut buf).unwrap().len();
+    /// assert_eq!(len, net.prefix_encoded_len());
+    /// assert_eq!(&buf[..len], &[20, 10, 1, 16]);
+    /// # }
+    /// ```
+    pub fn write_prefix<'a>(&self, buf: &'a mut [u8]) -> Result<&'a mut [u8], BufferTooSmall> {
+        write_prefix(&self.network().octets(), self.1.prefixlen(), buf)
+    }
+
+    /// Return the number of bytes of the BGP NLRI prefix encoding of this network.
+    pub fn prefix_encoded_len(&self) -> usize {
+        prefix_encoded_len(self.1.prefixlen())
+    }
+
+    /// Read a network in the BGP NLRI prefix encoding from a
+    /// [`Buf`](https://docs.rs/bytes/1/bytes/trait.Buf.html) and advance it. If the encoding is
+    /// invalid, the buffer is not advanced. This requires the `bytes` feature.
+    #[cfg(feature = "bytes")]
+    pub fn read_prefix_from_buf<B: Buf>(buf: &mut B) -> Result<Ipv4Network, MalformedAddress> {
+        let mut octets = [0; 4];
+        let prefixlen = read_prefix_buf(buf, IPV4_MAX_PREFIXLEN, &mut octets)?;
+        let mask = Ipv4Mask::from_prefixlen(prefixlen).map_err(|_| MalformedAddress)?;
+        let ip = Ipv4Address::from_slice_unchecked(&octets) & mask;
+        Ok(Ipv4Network(ip, mask))
+    }
+
+    /// Write the network in the BGP NLRI prefix encoding in a
+    /// [`BufMut`](https://docs.rs/bytes/1/bytes/trait.BufMut.html). This requires the `bytes`
+    /// feature.
+    #[cfg(feature = "bytes")]
+    pub fn write_prefix_to_buf<B: BufMut>(&self, buf: &mut B) -> Result<(), BufferTooSmall> {
+        let mut bytes = [0; 5];
+        let len = bytes.len() - self.write_prefix(&mut bytes)?.len();
+        write_buf(&bytes[..len], buf)
+    }
+}
+
+impl Ipv6Network {
+    /// Read a network in the BGP NLRI prefix encoding (see
+    /// [RFC4760](https://tools.ietf.org/html/rfc4760#section-5)), and return it along with the
+    /// remaining bytes. The bits of the last octet that are not part of the prefix are ignored.
+    ///
+    /// ```rust
+    /// # use ipaddr::Ipv6Network;
+    /// # use std::str::FromStr;
+    /// # fn main() {
+    /// let (net, rest) = Ipv6Network::read_prefix(&[32, 0x20, 0x01, 0x0d, 0xb8]).unwrap();
+    /// assert_eq!(net, Ipv6Network::from_str("2001:db8::/32").unwrap());
+    /// assert!(rest.is_empty());
+    /// # }
+    /// ```
+    pub fn read_prefix(bytes: &[u8]) -> Result<(Ipv6Network, &[u8]), MalformedAddress> {
+        let mut octets = [0; 16];
+        let (prefixlen, rest) = read_prefix(bytes, IPV6_MAX_PREFIXLEN, &mut octets)?;
+        let mask = Ipv6Mask::from_prefixlen(prefixlen).map_err(|_| MalformedAddress)?;
+        let ip = Ipv6Address::from_slice_unchecked(&octets) & mask;
+        Ok((Ipv6Network(ip, mask), rest))
+    }
+
+    /// Write the network in the BGP NLRI prefix encoding, and return the remaining bytes. Only
+    /// the network part of the address is written.
+    pub fn write_prefix<'a>(&self, buf: &'a mut [u8]) -> Result<&'a mut [u8], BufferTooSmall> {
+        write_prefix(&self.network().octets(), self.1.prefixlen(), buf)
+    }
+
+    /// Return the number of bytes of the BGP NLRI prefix encoding of this network.
+    pub fn prefix_encoded_len(&self) -> usize {
+        prefix_encoded_len(self.1.prefixlen())
+    }
+
+    /// Read a network in the BGP NLRI prefix encoding from a
+    /// [`Buf`](https://docs.rs/bytes/1/bytes/trait.Buf.html) and advance it. If the encoding is
+    /// invalid, the buffer is not advanced. This requires the `bytes` feature.
+    #[cfg(feature = "bytes")]
+    pub fn read_prefix_from_buf<B: Buf>(buf: &mut B) -> Result<Ipv6Network, MalformedAddress> {
+        let mut octets = [0; 16];
+        let prefixlen = read_prefix_buf(buf, IPV6_MAX_PREFIXLEN, &mut octets)?;
+        let mask = Ipv6Mask::from_prefixlen(prefixlen).map_err(|_| MalformedAddress)?;
+        let ip = Ipv6Address::from_slice_unchecked(&octets) & mask;
+        Ok(Ipv6Network(ip, mask))
+    }
+
+    /// Write the network in the BGP NLRI prefix encoding in a
+    /// [`BufMut`](https://docs.rs/bytes/1/bytes/trait.BufMut.html). This requires the `bytes`
+    /// feature.
+    #[cfg(feature = "bytes")]
+    pub fn write_prefix_to_buf<B: BufMut>(&self, buf: &mut B) -> Result<(), BufferTooSmall> {
+        let mut bytes = [0; 17];
+        let len = bytes.len() - self.write_prefix(&mut bytes)?.len();
+        write_buf(&bytes[..len], buf)
+    }
+}
+
+/// Split `bytes` after `n` bytes.
+fn split(bytes: &[u8], n: usize) -> Result<(&[u8], &[u8]), MalformedAddress> {
+    if bytes.len() < n {
+        return Err(MalformedAddress);
+    }
+    Ok(bytes.split_at(n))
+}
+
+/// Copy `bytes` at the beginning of `buf`, and return the rest of `buf`.
+fn write<'a>(bytes: &[u8], buf: &'a mut [u8]) -> Result<&'a mut [u8], BufferTooSmall> {
+    if buf.len() < bytes.len() {
+        return Err(BufferTooSmall);
+    }
+    let (head, rest) = buf.split_at_mut(bytes.len());
+    head.copy_from_slice(bytes);
+    Ok(rest)
+}
+
+/// Return the number of octets needed to hold a prefix of the given length.
+fn prefix_octets(prefixlen: u32) -> usize {
+    prefixlen.div_ceil(8) as usize
+}
+
+fn prefix_encoded_len(prefixlen: u32) -> usize {
+    1 + prefix_octets(prefixlen)
+}
+
+/// Read a prefix length and the octets of the prefix into `octets`, and return the prefix length
+/// along with the remaining bytes.
+fn read_prefix<'a>(
+    bytes: &'a [u8],
+    max_prefixlen: u8,
+    octets: &mut [u8],
+) -> Result<(u8, &'a [u8]), MalformedAddress> {
+    let (prefixlen, rest) = match bytes.split_first() {
+        Some((&prefixlen, rest)) if prefixlen <= max_prefixlen => (prefixlen, rest),
+        _ => return Err(MalformedAddress),
+    };
+    let (prefix, rest) = split(rest, prefix_octets(u32::from(prefixlen)))?;
+    octets[..prefix.len()].copy_from_slice(prefix);
+    Ok((prefixlen, rest))
+}
+
+fn write_prefix<'a>(
+    octets: &[u8],
+    prefixlen: u32,
+    buf: &'a mut [u8],
+) -> Result<&'a mut [u8], BufferTooSmall> {
+    if buf.len() < prefix_encoded_len(prefixlen) {
+        return Err(BufferTooSmall);
+    }
+    // prefix lengths are at most 128
+    buf[0] = prefixlen as u8;
+    write(&octets[..prefix_octets(prefixlen)], &mut buf[1..])
+}
+
+#[cfg(feature = "bytes")]
+fn read_buf<B: Buf>(buf: &mut B, octets: &mut [u8]) -> Result<(), MalformedAddress> {
+    if buf.remaining() < octets.len() {
+        return Err(MalformedAddress);
+    }
+    buf.copy_to_slice(octets);
+    Ok(())
+}
+
+#[cfg(feature = "bytes")]
+fn read_prefix_buf<B: Buf>(
+    buf: &mut B,
+    max_prefixlen: u8,
+    octets: &mut [u8],
+) -> Result<u8, MalformedAddress> {
+    let prefixlen = match buf.chunk().first() {
+        Some(&prefixlen) if prefixlen <= max_prefixlen => prefixlen,
+        _ => return Err(MalformedAddress),
+    };
+    let len = prefix_octets(u32::from(prefixlen));
+    if buf.remaining() < 1 + len {
+        return Err(MalformedAddress);
+    }
+    buf.advance(1);
+    buf.copy_to_slice(&mut octets[..len]);
+    Ok(prefixlen)
+}
+
+#[cfg(feature = "bytes")]
+fn write_buf<B: BufMut>(bytes: &[u8], buf: &mut B) -> Result<(), BufferTooSmall> {
+    if buf.remaining_mut() < bytes.len() {
+        return Err(BufferTooSmall);
+    }
+    buf.put_slice(bytes);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    #[test]
+    fn test_addresses() {
+        let ip = Ipv6Address(0x2001_0db8_0000_0000_0000_0000_0567_89ab);
+        let mut buf = [0; 20];
+        {
+            let rest = ip.write_to(&mut buf).unwrap();
+            assert_eq!(rest.len(), 4);
+            Ipv4Address(0xc000_0221).write_to(rest).unwrap();
+        }
+        let (ip6, rest) = Ipv6Address::read_from(&buf).unwrap();
+        assert_eq!(ip6, ip);
+        let (ip4, rest) = Ipv4Address::read_from(rest).unwrap();
+        assert_eq!(ip4, Ipv4Address(0xc000_0221));
+        assert!(rest.is_empty());
+        assert!(Ipv4Address::read_from(rest).is_err());
+        assert!(Ipv6Address::read_from(&buf[..15]).is_err());
+        assert!(ip.write_to(&mut buf[..15]).is_err());
+        assert!(Ipv4Address(0).write_to(&mut buf[..3]).is_err());
+    }
+
+    #[test]
+    fn test_prefixes() {
+        for (net, encoded) in &[
+            ("0.0.0.0/0", &[0][..]),
+            ("10.0.0.0/8", &[8, 10][..]),
+            ("192.0.2.128/25", &[25, 192, 0, 2, 128][..]),
+            ("192.0.2.33/32", &[32, 192, 0, 2, 33][..]),
+        ] {
+            let net = Ipv4Network::from_str(net).unwrap();
+            let mut buf = [0; 5];
+            let len = buf.len() - net.write_prefix(&mut buf).unwrap().len();
+            assert_eq!(&buf[..len], *encoded);
+            assert_eq!(net.prefix_encoded_len(), len);
+            assert_eq!(Ipv4Network::read_prefix(encoded).unwrap(), (net, &[][..]));
+        }
+        for (net, encoded) in &[
+            ("::/0", &[0][..]),
+            ("2001:db8::/33", &[33, 0x20, 0x01, 0x0d, 0xb8, 0x00][..]),
+        ] {
+            let net = Ipv6Network::from_str(net).unwrap();
+            let mut buf = [0; 17];
+            let len = buf.len() - net.write_prefix(&mut buf).unwrap().len();
+            assert_eq!(&buf[..len], *encoded);
+            assert_eq!(net.prefix_encoded_len(), len);
+            assert_eq!(Ipv6Network::read_prefix(encoded).unwrap(), (net, &[][..]));
+        }
+
+        // host bits are dropped
+        let (net, _) = Ipv4Network::read_prefix(&[9, 10, 0xff]).unwrap();
+        assert_eq!(net, Ipv4Network::from_str("10.128.0.0/9").unwrap());
+
+        assert!(Ipv4Network::read_prefix(&[]).is_err());
+        assert!(Ipv4Network::read_prefix(&[9, 10]).is_err());
+        assert!(Ipv6Network::read_prefix(&[129]).is_err());
+        let net = Ipv6Network::from_str("2001:db8::/32").unwrap();
+        assert!(net.write_prefix(&mut [0; 4]).is_err());
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn test_bytes() {
+        use bytes::BytesMut;
+
+        let mut buf = BytesMut::new();
+        Ipv4Address(0xc000_0221).write_to_buf(&mut buf).unwrap();
+        let net = Ipv6Network::from_str("2001:db8::/33").unwrap();
+        net.write_prefix_to_buf(&mut buf).unwrap();
+        assert_eq!(buf.len(), 10);
+
+        let mut buf = buf.freeze();
+        assert_eq!(
+            Ipv4Address::read_from_buf(&mut buf).unwrap(),
+            Ipv4Address(0xc000_0221)
+        );
+        assert!(Ipv4Network::read_prefix_from_buf(&mut buf).is_err());
+        assert_eq!(buf.len(), 6);
+        assert_eq!(Ipv6Network::read_prefix_from_buf(&mut buf).unwrap(), net);
+        assert!(Ipv6Address::read_from_buf(&mut buf).is_err());
+    }
+}